@@ -6,12 +6,7 @@ fn main() {
     // Example 1: Basic compound interest
     println!("Example 1: Basic Compound Interest");
     println!("-----------------------------------");
-    let params = CompoundInterestParams {
-        principal: 10000.0,
-        annual_rate: 0.06,  // 6%
-        compounds_per_year: 12,  // Monthly
-        years: 20.0,
-    };
+    let params = CompoundInterestParams::new(10000.0, 0.06, Compounding::Monthly, 20.0);  // 6% for 20 years
 
     let result = calculate_compound_interest(&params);
     println!("Initial Principal: {}", format_currency(params.principal));
@@ -26,7 +21,7 @@ fn main() {
     println!("Example 2: Compound Interest with Monthly Contributions");
     println!("------------------------------------------------------");
     let monthly_contribution = 500.0;
-    let result_with_contributions = calculate_compound_interest_with_contributions(&params, monthly_contribution);
+    let result_with_contributions = calculate_compound_interest_with_contributions(&params, &Contribution::new(monthly_contribution));
     
     let total_contributions = monthly_contribution * params.years * 12.0;
     println!("Initial Principal: {}", format_currency(params.principal));
@@ -39,7 +34,7 @@ fn main() {
     // Example 3: Time to double your money
     println!("Example 3: Time to Double Your Money");
     println!("------------------------------------");
-    let years_to_double = calculate_time_to_target(10000.0, 20000.0, 0.07, 12);
+    let years_to_double = calculate_time_to_target(10000.0, 20000.0, 0.07, Compounding::Monthly);
     println!("Principal: {}", format_currency(10000.0));
     println!("Target: {}", format_currency(20000.0));
     println!("Annual Rate: {}", format_percentage(0.07));
@@ -50,7 +45,7 @@ fn main() {
     // Example 4: Required principal for target
     println!("Example 4: Required Principal for Target");
     println!("----------------------------------------");
-    let required_principal = calculate_principal_for_target(100000.0, 0.05, 12, 15.0);
+    let required_principal = calculate_principal_for_target(100000.0, 0.05, Compounding::Monthly, 15.0);
     println!("Target Amount: {}", format_currency(100000.0));
     println!("Annual Rate: {}", format_percentage(0.05));
     println!("Time Period: {:.1} years", 15.0);
@@ -60,12 +55,7 @@ fn main() {
     // Example 5: Year-by-year breakdown
     println!("Example 5: Year-by-Year Breakdown (First 5 years)");
     println!("------------------------------------------------");
-    let short_params = CompoundInterestParams {
-        principal: 5000.0,
-        annual_rate: 0.08,  // 8%
-        compounds_per_year: 12,  // Monthly
-        years: 5.0,
-    };
+    let short_params = CompoundInterestParams::new(5000.0, 0.08, Compounding::Monthly, 5.0);  // 8% for 5 years
     
     let breakdown = generate_breakdown(&short_params);
     println!("Initial Principal: {}", format_currency(short_params.principal));
@@ -90,19 +80,16 @@ fn main() {
     // Example 6: Comparison of different compounding frequencies
     println!("Example 6: Compounding Frequency Comparison");
     println!("-------------------------------------------");
-    let base_params = CompoundInterestParams {
-        principal: 10000.0,
-        annual_rate: 0.05,  // 5%
-        compounds_per_year: 1,  // Will be overridden for each frequency
-        years: 10.0,
-    };
+    // 5% for 10 years; the compounding is overridden for each frequency
+    let base_params = CompoundInterestParams::new(10000.0, 0.05, Compounding::Annually, 10.0);
 
     let frequencies = vec![
-        ("Annually", 1),
-        ("Semi-annually", 2),
-        ("Quarterly", 4),
-        ("Monthly", 12),
-        ("Daily", 365),
+        ("Annually", Compounding::Annually),
+        ("Semi-annually", Compounding::Semiannually),
+        ("Quarterly", Compounding::Quarterly),
+        ("Monthly", Compounding::Monthly),
+        ("Daily", Compounding::Daily),
+        ("Continuous", Compounding::Continuous),
     ];
 
     println!("Initial Principal: {}", format_currency(base_params.principal));
@@ -114,8 +101,8 @@ fn main() {
 
     for (name, freq) in frequencies {
         let params = CompoundInterestParams {
-            compounds_per_year: freq,
-            ..base_params
+            compounding: freq,
+            ..base_params.clone()
         };
         let result = calculate_compound_interest(&params);
         println!(
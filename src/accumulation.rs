@@ -0,0 +1,141 @@
+use crate::error::CalcError;
+use crate::{Escalation, PaymentFrequency};
+
+/// A plan of regular contributions whose profit is taxed each year
+///
+/// This is the model of `calculate_weekly_with_yearly_tax` with the return
+/// allowed to change from one period to the next: each period the balance
+/// earns that period's return and the contribution is added at the end of the
+/// period. At the end of every year the year's profit, if positive, is taxed,
+/// and a final partial year is taxed pro rata.
+#[derive(Debug, Clone)]
+pub struct AccumulationPlan {
+    /// Balance at the start of the plan
+    pub principal: f64,
+    /// Amount contributed each period during the first year
+    pub contribution: f64,
+    /// How the contribution grows each year
+    pub escalation: Escalation,
+    /// Length of each return period, such as weekly or monthly
+    pub frequency: PaymentFrequency,
+    /// Tax rate on each year's profit (as a decimal, e.g., 0.37 for 37%)
+    pub capital_gains_tax: f64,
+}
+
+/// Outcome of running an accumulation plan over a series of returns
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccumulationResult {
+    /// Balance at the end, after tax
+    pub final_amount: f64,
+    /// Total amount contributed
+    pub total_contributions: f64,
+    /// Total profit before tax
+    pub total_profit: f64,
+    /// Total tax paid
+    pub total_tax: f64,
+}
+
+impl AccumulationPlan {
+    /// Check the plan can be run
+    pub(crate) fn validate(&self) -> Result<(), CalcError> {
+        if !(0.0..1.0).contains(&self.capital_gains_tax) {
+            return Err(CalcError::InvalidInput("capital gains tax must be at least 0 and below 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Run an accumulation plan with one return per period
+///
+/// `returns` holds the return for each period in order (as decimals, e.g.,
+/// 0.01 for 1%), so its length sets the horizon. A return below -100%, which
+/// fat-tailed or normal draws can produce, loses the whole balance but no more,
+/// so the balance never goes negative.
+pub fn accumulate(plan: &AccumulationPlan, returns: &[f64]) -> AccumulationResult {
+    let periods_per_year = plan.frequency.periods_per_year() as usize;
+
+    let mut balance = plan.principal;
+    let mut total_contributions = 0.0;
+    let mut total_tax = 0.0;
+
+    for (year, year_returns) in returns.chunks(periods_per_year).enumerate() {
+        let contribution = plan.escalation.amount_in_year(plan.contribution, year as u32);
+        let year_start = balance;
+        for rate in year_returns {
+            balance = balance * (1.0 + rate.max(-1.0)) + contribution;
+        }
+        let year_contributions = contribution * year_returns.len() as f64;
+        total_contributions += year_contributions;
+
+        // Partial years are taxed pro rata
+        let year_profit = balance - year_start - year_contributions;
+        let year_fraction = year_returns.len() as f64 / periods_per_year as f64;
+        let year_tax = if year_profit > 0.0 { year_profit * plan.capital_gains_tax * year_fraction } else { 0.0 };
+        total_tax += year_tax;
+        balance -= year_tax;
+    }
+
+    AccumulationResult {
+        final_amount: balance,
+        total_contributions,
+        total_profit: balance + total_tax - plan.principal - total_contributions,
+        total_tax,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_weekly_with_yearly_tax_escalating;
+
+    #[test]
+    fn test_constant_returns_match_weekly_model() {
+        let plan = AccumulationPlan {
+            principal: 10000.0,
+            contribution: 100.0,
+            escalation: Escalation::Percent(0.05),
+            frequency: PaymentFrequency::Weekly,
+            capital_gains_tax: 0.3,
+        };
+        let result = accumulate(&plan, &[0.004; 130]);
+        let (final_amount, profit, tax) =
+            calculate_weekly_with_yearly_tax_escalating(10000.0, 0.004, 130, 100.0, Escalation::Percent(0.05), 0.3);
+
+        assert!((result.final_amount - final_amount).abs() < 1e-6);
+        assert!((result.total_profit - profit).abs() < 1e-6);
+        assert!((result.total_tax - tax).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_losing_years_pay_no_tax() {
+        let plan = AccumulationPlan {
+            principal: 1200.0,
+            contribution: 0.0,
+            escalation: Escalation::None,
+            frequency: PaymentFrequency::Monthly,
+            capital_gains_tax: 0.5,
+        };
+        // A 10% gain in the first year is taxed, the loss in the second is not
+        let mut returns = vec![0.0; 24];
+        returns[0] = 0.1;
+        returns[12] = -0.1;
+        let result = accumulate(&plan, &returns);
+
+        assert!((result.total_tax - 60.0).abs() < 1e-9);
+        assert!((result.final_amount - 1260.0 * 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_returns_below_total_loss_are_clamped() {
+        let plan = AccumulationPlan {
+            principal: 1000.0,
+            contribution: 50.0,
+            escalation: Escalation::None,
+            frequency: PaymentFrequency::Monthly,
+            capital_gains_tax: 0.0,
+        };
+        // The -250% month wipes out the balance, leaving only later contributions
+        let result = accumulate(&plan, &[0.0, -2.5, 0.0]);
+        assert!((result.final_amount - 100.0).abs() < 1e-9);
+    }
+}
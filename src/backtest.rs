@@ -0,0 +1,174 @@
+use crate::accumulation::{accumulate, AccumulationPlan, AccumulationResult};
+use crate::date::Date;
+use crate::error::CalcError;
+use crate::simulation::PercentileReport;
+
+/// One period of a historical return series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodReturn {
+    /// Date of the period, if the series has one
+    pub date: Option<Date>,
+    /// Return over the period (as a decimal, e.g., 0.01 for 1%)
+    pub rate: f64,
+}
+
+/// An accumulation plan run over one stretch of a return series
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestWindow {
+    /// Index of the first period of the window in the series
+    pub start: usize,
+    /// Outcome of the plan over the window
+    pub result: AccumulationResult,
+}
+
+/// An accumulation plan run over every window of a return series
+#[derive(Debug, Clone)]
+pub struct RollingBacktest {
+    /// Every window, in order of start period
+    pub windows: Vec<BacktestWindow>,
+    /// Window with the highest final amount
+    pub best: BacktestWindow,
+    /// Window with the lowest final amount
+    pub worst: BacktestWindow,
+    /// Window with the median final amount (the lower of the two middle ones for an even count)
+    pub median: BacktestWindow,
+    /// Distribution of the final amount across the windows
+    pub final_amount: PercentileReport,
+}
+
+/// Parse a return series written one period per line
+///
+/// Each line is either `rate` or `date,rate`, with dates as `YYYY-MM-DD` and
+/// rates as decimals (`0.012`) or percentages (`1.2%`). When a line has more
+/// columns the last one is the rate. Blank lines and lines starting with `#`
+/// are skipped, as is the first remaining line if its rate does not parse,
+/// since that is a header row. Any other line that does not parse is an error.
+pub fn parse_returns(text: &str) -> Result<Vec<PeriodReturn>, CalcError> {
+    let mut returns = Vec::new();
+    let mut header_allowed = true;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_first_line = std::mem::replace(&mut header_allowed, false);
+        let invalid = |message: String| CalcError::InvalidInput(format!("line {}: {}", index + 1, message));
+
+        let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+        let rate_text = columns[columns.len() - 1];
+        let rate = match parse_rate(rate_text) {
+            Some(rate) => rate,
+            None if is_first_line => continue,
+            None => return Err(invalid(format!("invalid return '{}'", rate_text))),
+        };
+        let date = match columns.len() {
+            1 => None,
+            _ => Some(columns[0].parse::<Date>().map_err(|error| invalid(error.to_string()))?),
+        };
+
+        returns.push(PeriodReturn { date, rate });
+    }
+
+    Ok(returns)
+}
+
+/// Parse a rate written as a decimal or a percentage
+fn parse_rate(text: &str) -> Option<f64> {
+    match text.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok().map(|rate| rate / 100.0),
+        None => text.parse().ok(),
+    }
+}
+
+/// Run an accumulation plan over every window of `window` consecutive periods
+///
+/// Windows start at each period in turn, so a series of n periods gives
+/// n - window + 1 overlapping histories. Each runs through `accumulate`, with
+/// the same contributions and yearly tax as `calculate_weekly_with_yearly_tax`.
+pub fn rolling_backtest(plan: &AccumulationPlan, returns: &[f64], window: usize) -> Result<RollingBacktest, CalcError> {
+    plan.validate()?;
+    if window == 0 || window > returns.len() {
+        return Err(CalcError::InvalidInput(format!(
+            "the window must be between 1 and the {} periods in the series",
+            returns.len()
+        )));
+    }
+
+    let windows: Vec<BacktestWindow> = returns
+        .windows(window)
+        .enumerate()
+        .map(|(start, window_returns)| BacktestWindow {
+            start,
+            result: accumulate(plan, window_returns),
+        })
+        .collect();
+
+    let mut ranked: Vec<&BacktestWindow> = windows.iter().collect();
+    ranked.sort_by(|a, b| a.result.final_amount.total_cmp(&b.result.final_amount));
+    let outcomes: Vec<f64> = windows.iter().map(|window| window.result.final_amount).collect();
+
+    Ok(RollingBacktest {
+        best: ranked[ranked.len() - 1].clone(),
+        worst: ranked[0].clone(),
+        median: ranked[(ranked.len() - 1) / 2].clone(),
+        final_amount: PercentileReport::from_outcomes(&outcomes).ok_or(CalcError::Unreachable)?,
+        windows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Escalation, PaymentFrequency};
+
+    fn plan() -> AccumulationPlan {
+        AccumulationPlan {
+            principal: 1000.0,
+            contribution: 0.0,
+            escalation: Escalation::None,
+            frequency: PaymentFrequency::Monthly,
+            capital_gains_tax: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_parse_returns() {
+        let text = "date,return\n2024-01-31,0.012\n\n# a bad month\n2024-02-29, -3.5%\n2024-03-31,0.004\n";
+        let returns = parse_returns(text).unwrap();
+        assert_eq!(returns.len(), 3);
+        assert_eq!(returns[1].date, Some(Date::from_ymd(2024, 2, 29).unwrap()));
+        assert!((returns[1].rate + 0.035).abs() < 1e-12);
+
+        let undated = parse_returns("0.01\n0.02\n").unwrap();
+        assert_eq!(undated[1], PeriodReturn { date: None, rate: 0.02 });
+
+        let error = parse_returns("0.01\nabc\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        // Only one header row is skipped, so a bad first return is still reported
+        let error = parse_returns("date,return\n2024-01-31,0.0x1\n2024-02-29,0.01\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+        let error = parse_returns("returns\nmonthly\n0.01\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_rolling_windows() {
+        // Windows of two periods: +10% +10%, +10% -50%, -50% +20%, +20% +10%
+        let returns = [0.1, 0.1, -0.5, 0.2, 0.1];
+        let backtest = rolling_backtest(&plan(), &returns, 2).unwrap();
+
+        assert_eq!(backtest.windows.len(), 4);
+        assert_eq!(backtest.best.start, 3);
+        assert!((backtest.best.result.final_amount - 1320.0).abs() < 1e-9);
+        assert_eq!(backtest.worst.start, 1);
+        assert!((backtest.worst.result.final_amount - 550.0).abs() < 1e-9);
+        // Ranked 550, 600, 1210, 1320: the lower middle one starts at the third period
+        assert_eq!(backtest.median.start, 2);
+        assert_eq!(backtest.final_amount.count, 4);
+
+        assert!(rolling_backtest(&plan(), &returns, 6).is_err());
+        assert!(rolling_backtest(&plan(), &returns, 0).is_err());
+    }
+}
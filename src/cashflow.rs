@@ -0,0 +1,184 @@
+use crate::date::Date;
+use crate::error::CalcError;
+use crate::solver;
+
+/// A single dated cash flow
+///
+/// Money paid in (a deposit or purchase) is negative and money received (a
+/// withdrawal, sale or closing balance) is positive, as in spreadsheets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CashFlow {
+    /// Date the money moves
+    pub date: Date,
+    /// Amount, negative for money paid in
+    pub amount: f64,
+}
+
+/// Net present value of cash flows one period apart, the first at time zero
+///
+/// Unlike the spreadsheet NPV function, the first flow is not discounted.
+pub fn npv(rate: f64, cash_flows: &[f64]) -> f64 {
+    cash_flows
+        .iter()
+        .enumerate()
+        .map(|(period, amount)| amount / (1.0 + rate).powf(period as f64))
+        .sum()
+}
+
+/// Internal rate of return of cash flows one period apart
+///
+/// Returns the rate per period at which `npv` is zero.
+pub fn irr(cash_flows: &[f64]) -> Result<f64, CalcError> {
+    check_signs(cash_flows.iter().copied())?;
+    solve_rate(|rate| npv(rate, cash_flows))
+}
+
+/// Net present value of dated cash flows, discounted to the earliest date
+///
+/// `annual_rate` is an effective annual rate applied over actual days / 365,
+/// as in the spreadsheet XNPV function.
+pub fn xnpv(annual_rate: f64, cash_flows: &[CashFlow]) -> f64 {
+    let start = match cash_flows.iter().map(|flow| flow.date).min() {
+        Some(start) => start,
+        None => return 0.0,
+    };
+    cash_flows
+        .iter()
+        .map(|flow| {
+            let years = start.days_until(flow.date) as f64 / 365.0;
+            flow.amount / (1.0 + annual_rate).powf(years)
+        })
+        .sum()
+}
+
+/// Internal rate of return of dated cash flows, as an effective annual rate
+pub fn xirr(cash_flows: &[CashFlow]) -> Result<f64, CalcError> {
+    check_signs(cash_flows.iter().map(|flow| flow.amount))?;
+    solve_rate(|rate| xnpv(rate, cash_flows))
+}
+
+/// Parse cash flows written one per line as `date,amount`
+///
+/// Dates are `YYYY-MM-DD`. Blank lines and lines starting with `#` are
+/// skipped, as is the first remaining line if neither its date nor its amount
+/// parses, since that is a header row. Any other line that does not parse is
+/// an error.
+pub fn parse_cash_flows(text: &str) -> Result<Vec<CashFlow>, CalcError> {
+    let mut flows = Vec::new();
+    let mut header_allowed = true;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_first_line = std::mem::replace(&mut header_allowed, false);
+        let invalid = |message: String| CalcError::InvalidInput(format!("line {}: {}", index + 1, message));
+
+        let (date, amount) = line
+            .split_once(',')
+            .ok_or_else(|| invalid("expected date,amount".to_string()))?;
+        let (date, amount) = (date.trim(), amount.trim());
+        let date = match date.parse::<Date>() {
+            Ok(date) => date,
+            Err(_) if is_first_line && amount.parse::<f64>().is_err() => continue,
+            Err(error) => return Err(invalid(error.to_string())),
+        };
+        let amount = amount
+            .parse::<f64>()
+            .map_err(|_| invalid(format!("invalid amount '{}'", amount)))?;
+
+        flows.push(CashFlow { date, amount });
+    }
+
+    Ok(flows)
+}
+
+/// A rate of return only exists if money goes both in and out
+fn check_signs<I: Iterator<Item = f64>>(amounts: I) -> Result<(), CalcError> {
+    let (mut paid, mut received) = (false, false);
+    for amount in amounts {
+        paid |= amount < 0.0;
+        received |= amount > 0.0;
+    }
+    if paid && received {
+        Ok(())
+    } else {
+        Err(CalcError::InvalidInput("cash flows must include money paid in and money received".to_string()))
+    }
+}
+
+/// Solve for the rate above -100% at which the present value is zero
+fn solve_rate<F: Fn(f64) -> f64>(present_value: F) -> Result<f64, CalcError> {
+    // Newton's method from 10%, the spreadsheet default guess, finds the usual answer
+    if let Ok(rate) = solver::newton(&present_value, 0.1, 1e-12, 50) {
+        if rate > -1.0 {
+            return Ok(rate);
+        }
+    }
+
+    // Otherwise look for a sign change and narrow it down
+    let mut low = -0.99;
+    for high in [-0.9, -0.75, -0.5, -0.25, 0.0, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 100.0] {
+        if present_value(low) * present_value(high) <= 0.0 {
+            return solver::ridders(&present_value, low, high, 1e-12);
+        }
+        low = high;
+    }
+    Err(CalcError::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(date: &str, amount: f64) -> CashFlow {
+        CashFlow {
+            date: date.parse().unwrap(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_npv_and_irr() {
+        assert!((npv(0.1, &[-100.0, 110.0])).abs() < 1e-12);
+        assert!((irr(&[-100.0, 110.0]).unwrap() - 0.1).abs() < 1e-10);
+
+        // Excel IRR reference example
+        let flows = [-70000.0, 12000.0, 15000.0, 18000.0, 21000.0, 26000.0];
+        assert!((irr(&flows).unwrap() - 0.086630948).abs() < 1e-8);
+
+        assert!(matches!(irr(&[100.0, 50.0]), Err(CalcError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_xnpv_and_xirr() {
+        // Excel XNPV and XIRR reference example
+        let flows = [
+            flow("2008-01-01", -10000.0),
+            flow("2008-03-01", 2750.0),
+            flow("2008-10-30", 4250.0),
+            flow("2009-02-15", 3250.0),
+            flow("2009-04-01", 2750.0),
+        ];
+        assert!((xnpv(0.09, &flows) - 2086.65).abs() < 0.005);
+        assert!((xirr(&flows).unwrap() - 0.373362535).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_parse_cash_flows() {
+        let text = "date,amount\n2024-01-01,-1000\n\n# top up\n2024-06-01, -500.50\n2025-01-01,1600\n";
+        let flows = parse_cash_flows(text).unwrap();
+        assert_eq!(flows.len(), 3);
+        assert_eq!(flows[1], flow("2024-06-01", -500.5));
+
+        let error = parse_cash_flows("2024-01-01,-1000\n2024-13-01,5\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        // Only one header row is skipped, so a bad first flow is still reported
+        let error = parse_cash_flows("date,amount\n2024-0l-01,-1000\n2025-01-01,1100\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+        let error = parse_cash_flows("date,amount\nwhen,how much\n2024-01-01,-1000\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+}
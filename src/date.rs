@@ -0,0 +1,172 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A calendar date in the proleptic Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+/// Error returned when a date string cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDateError(String);
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date '{}', expected YYYY-MM-DD", self.0)
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+/// Whether `year` is a leap year
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Number of days in the given month of `year`
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Number of days in `year`
+pub fn days_in_year(year: i32) -> u32 {
+    if is_leap_year(year) { 366 } else { 365 }
+}
+
+impl Date {
+    /// Create a date, returning `None` if the day or month is out of range
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        if day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Calendar year
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Month of the year, from 1 to 12
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// Day of the month, starting at 1
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Whether this date is the last day of its month
+    pub fn is_end_of_month(&self) -> bool {
+        self.day == days_in_month(self.year, self.month)
+    }
+
+    /// Days since 1970-01-01 (negative before the epoch)
+    pub fn to_days(&self) -> i64 {
+        // Howard Hinnant's days_from_civil algorithm
+        let y = if self.month <= 2 { self.year as i64 - 1 } else { self.year as i64 };
+        let era = if y >= 0 { y } else { y - 399 } / 400;
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// Date from a number of days since 1970-01-01
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719468;
+        let era = if z >= 0 { z } else { z - 146096 } / 146097;
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /// Actual number of days from this date to `other` (negative if `other` is earlier)
+    pub fn days_until(&self, other: Date) -> i64 {
+        other.to_days() - self.to_days()
+    }
+
+    /// This date moved by a number of days
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.to_days() + days)
+    }
+
+    /// This date moved by a number of months, clamping to the end of shorter months
+    pub fn add_months(&self, months: i32) -> Date {
+        let total = self.year * 12 + (self.month as i32 - 1) + months;
+        let year = total.div_euclid(12);
+        let month = total.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Date { year, month, day }
+    }
+
+    /// This date moved by a number of years, clamping 29 February to 28 February
+    pub fn add_years(&self, years: i32) -> Date {
+        self.add_months(years * 12)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    /// Parse an ISO 8601 date (YYYY-MM-DD)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDateError(s.to_string());
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        let month = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        let day = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        Date::from_ymd(year, month, day).ok_or_else(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_round_trip() {
+        let date = Date::from_ymd(2024, 2, 29).unwrap();
+        assert_eq!(Date::from_days(date.to_days()), date);
+        assert_eq!(Date::from_ymd(1970, 1, 1).unwrap().to_days(), 0);
+        assert_eq!(Date::from_ymd(2023, 2, 29), None);
+    }
+
+    #[test]
+    fn test_month_arithmetic() {
+        let jan31 = Date::from_ymd(2024, 1, 31).unwrap();
+        assert_eq!(jan31.add_months(1), Date::from_ymd(2024, 2, 29).unwrap());
+        assert_eq!(jan31.add_months(-2), Date::from_ymd(2023, 11, 30).unwrap());
+        assert_eq!(jan31.days_until(Date::from_ymd(2024, 3, 1).unwrap()), 30);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let date: Date = "2024-03-05".parse().unwrap();
+        assert_eq!(date.to_string(), "2024-03-05");
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert!("not a date".parse::<Date>().is_err());
+    }
+}
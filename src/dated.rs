@@ -0,0 +1,339 @@
+use crate::date::Date;
+use crate::daycount::DayCount;
+use crate::error::CalcError;
+use crate::{calculate_weekly_with_yearly_tax, real_values, Compounding, CompoundInterestResult};
+
+/// Parameters for a compound interest calculation over a calendar date range
+#[derive(Debug, Clone)]
+pub struct DatedParams {
+    /// Initial principal amount
+    pub principal: f64,
+    /// Annual interest rate (as a decimal, e.g., 0.05 for 5%)
+    pub annual_rate: f64,
+    /// How often interest is compounded
+    pub compounding: Compounding,
+    /// Date the principal is deposited
+    pub start: Date,
+    /// Date the balance is measured
+    pub end: Date,
+    /// Day-count convention for the final partial period, continuous accrual
+    /// and the inflation horizon
+    pub day_count: DayCount,
+    /// Expected annual inflation rate used for the real results (0 for none)
+    pub inflation_rate: f64,
+}
+
+impl DatedParams {
+    /// Build parameters that run for a number of whole compounding periods from `start`
+    ///
+    /// Continuous compounding has no natural period, so its periods are whole years.
+    /// The day count defaults to ACT/ACT.
+    pub fn for_periods(
+        principal: f64,
+        annual_rate: f64,
+        compounding: Compounding,
+        start: Date,
+        periods: u32,
+    ) -> Self {
+        DatedParams {
+            principal,
+            annual_rate,
+            compounding,
+            start,
+            end: period_boundary(start, compounding, periods as i64),
+            day_count: DayCount::ActAct,
+            inflation_rate: 0.0,
+        }
+    }
+}
+
+/// How a date range splits into compounding periods
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodCount {
+    /// Number of complete compounding periods
+    pub whole_periods: u32,
+    /// Fraction of the following period that has elapsed, by actual days
+    pub partial_period: f64,
+    /// Actual number of days in the range
+    pub days: i64,
+    /// Number of 29 Februaries falling inside the range
+    pub leap_days: u32,
+}
+
+/// One row of a dated breakdown
+#[derive(Debug, Clone)]
+pub struct DatedBreakdownRow {
+    /// Year number, counted from the start date (a final partial year is rounded up)
+    pub year: u32,
+    /// Date the balance is measured
+    pub date: Date,
+    /// Balance and interest from the start date up to `date`
+    pub result: CompoundInterestResult,
+}
+
+enum Step {
+    Months(i64),
+    Days(i64),
+}
+
+fn step(compounding: Compounding) -> Step {
+    match compounding.periods_per_year() {
+        Some(52.0) => Step::Days(7),
+        Some(365.0) => Step::Days(1),
+        Some(n) if n <= 12.0 && 12.0 % n == 0.0 => Step::Months((12.0 / n) as i64),
+        Some(n) => Step::Days((365.0 / n).round().max(1.0) as i64),
+        None => Step::Months(12),
+    }
+}
+
+/// Date on which the given compounding period ends, counting from `start`
+///
+/// Monthly and longer periods follow the calendar, keeping the start day where the
+/// month allows it; weekly and daily periods are a fixed number of days. Custom
+/// frequencies that do not divide a year into whole months use the nearest whole
+/// number of days, and frequencies above daily step one day at a time.
+pub fn period_boundary(start: Date, compounding: Compounding, period: i64) -> Date {
+    match step(compounding) {
+        Step::Months(months) => start.add_months((months * period) as i32),
+        Step::Days(days) => start.add_days(days * period),
+    }
+}
+
+/// Split the range from `start` to `end` into whole and partial compounding periods
+///
+/// Returns `CalcError::InvalidInput` if `end` is before `start`.
+pub fn count_periods(start: Date, end: Date, compounding: Compounding) -> Result<PeriodCount, CalcError> {
+    if end < start {
+        return Err(CalcError::InvalidInput("the end date must not be before the start date".to_string()));
+    }
+    let days = start.days_until(end);
+    let mut whole = match step(compounding) {
+        Step::Months(months) => {
+            let elapsed = (end.year() - start.year()) as i64 * 12 + end.month() as i64 - start.month() as i64;
+            (elapsed / months).max(0)
+        }
+        Step::Days(step_days) => days / step_days,
+    };
+    if whole > 0 && period_boundary(start, compounding, whole) > end {
+        whole -= 1;
+    }
+
+    let last = period_boundary(start, compounding, whole);
+    let next = period_boundary(start, compounding, whole + 1);
+    let partial_period = last.days_until(end) as f64 / last.days_until(next) as f64;
+
+    Ok(PeriodCount {
+        whole_periods: whole as u32,
+        partial_period,
+        days,
+        leap_days: count_leap_days(start, end),
+    })
+}
+
+/// Number of 29 Februaries after `start` up to and including `end`
+fn count_leap_days(start: Date, end: Date) -> u32 {
+    (start.year()..=end.year())
+        .filter_map(|year| Date::from_ymd(year, 2, 29))
+        .filter(|leap_day| *leap_day > start && *leap_day <= end)
+        .count() as u32
+}
+
+/// Calculate compound interest between two calendar dates
+///
+/// Interest compounds once per whole period, and the final partial period
+/// accrues for the year fraction `params.day_count` gives it (a 15-day stub of
+/// monthly compounding is half a period under ACT/360).
+/// Continuous compounding, and frequencies above daily whose periods are
+/// shorter than a day, use the day-count year fraction directly. Returns
+/// `CalcError::InvalidInput` if the end date is before the start date.
+pub fn calculate_compound_interest_dated(params: &DatedParams) -> Result<CompoundInterestResult, CalcError> {
+    let principal = params.principal;
+    let rate = params.annual_rate;
+    let count = count_periods(params.start, params.end, params.compounding)?;
+    let years = params.day_count.year_fraction(params.start, params.end);
+
+    let growth = match params.compounding.periods_per_year() {
+        Some(n) if n > 365.0 => (1.0 + rate / n).powf(n * years),
+        Some(n) => {
+            let last = period_boundary(params.start, params.compounding, count.whole_periods as i64);
+            let partial_periods = params.day_count.year_fraction(last, params.end) * n;
+            (1.0 + rate / n).powf(count.whole_periods as f64 + partial_periods)
+        }
+        None => (rate * years).exp(),
+    };
+
+    let final_amount = principal * growth;
+    let effective_annual_rate = params.compounding.effective_annual_rate(rate);
+    let (real_final_amount, real_total_interest, real_effective_annual_rate) =
+        real_values(final_amount, principal, effective_annual_rate, params.inflation_rate, years);
+    Ok(CompoundInterestResult {
+        final_amount,
+        total_interest: final_amount - principal,
+        principal,
+        effective_annual_rate,
+        real_final_amount,
+        real_total_interest,
+        real_effective_annual_rate,
+    })
+}
+
+/// Generate a breakdown with one row per anniversary of the start date, plus a
+/// final row on the end date if it falls part way through a year
+///
+/// Returns `CalcError::InvalidInput` if the end date is before the start date.
+pub fn generate_dated_breakdown(params: &DatedParams) -> Result<Vec<DatedBreakdownRow>, CalcError> {
+    let mut rows = Vec::new();
+    let mut year = 1;
+
+    loop {
+        let anniversary = params.start.add_years(year as i32);
+        let date = if anniversary < params.end { anniversary } else { params.end };
+        let row_params = DatedParams { end: date, ..params.clone() };
+        rows.push(DatedBreakdownRow {
+            year,
+            date,
+            result: calculate_compound_interest_dated(&row_params)?,
+        });
+        if date == params.end {
+            break;
+        }
+        year += 1;
+    }
+
+    Ok(rows)
+}
+
+/// Run `calculate_weekly_with_yearly_tax` over the whole weeks between two dates
+///
+/// Any days left over after the last whole week are not compounded. Returns
+/// `CalcError::InvalidInput` if `end` is before `start`.
+pub fn calculate_weekly_with_yearly_tax_between(
+    principal: f64,
+    weekly_rate: f64,
+    start: Date,
+    end: Date,
+    weekly_contribution: f64,
+    capital_gains_tax: f64,
+) -> Result<(f64, f64, f64), CalcError> {
+    if end < start {
+        return Err(CalcError::InvalidInput("the end date must not be before the start date".to_string()));
+    }
+    let weeks = (start.days_until(end) / 7) as u32;
+    Ok(calculate_weekly_with_yearly_tax(principal, weekly_rate, weeks, weekly_contribution, capital_gains_tax))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> Date {
+        Date::from_ymd(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_count_periods() {
+        let count = count_periods(date(2024, 1, 31), date(2024, 4, 15), Compounding::Monthly).unwrap();
+        // Jan 31 -> Feb 29 -> Mar 31, then 15 of the 30 days to Apr 30
+        assert_eq!(count.whole_periods, 2);
+        assert!((count.partial_period - 0.5).abs() < 1e-12);
+        assert_eq!(count.leap_days, 1);
+
+        let weekly = count_periods(date(2023, 1, 1), date(2024, 1, 1), Compounding::Weekly).unwrap();
+        assert_eq!(weekly.whole_periods, 52);
+        assert!((weekly.partial_period - 1.0 / 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dated_matches_fractional_years_on_whole_periods() {
+        let params = DatedParams::for_periods(1000.0, 0.05, Compounding::Quarterly, date(2020, 2, 29), 40);
+        assert_eq!(params.end, date(2030, 2, 28));
+
+        let result = calculate_compound_interest_dated(&params).unwrap();
+        let expected = 1000.0 * (1.0 + 0.05 / 4.0f64).powf(40.0);
+        assert!((result.final_amount - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dated_breakdown_rows() {
+        let params = DatedParams {
+            principal: 1000.0,
+            annual_rate: 0.05,
+            compounding: Compounding::Annually,
+            start: date(2024, 3, 1),
+            end: date(2026, 9, 1),
+            day_count: DayCount::ActAct,
+            inflation_rate: 0.0,
+        };
+
+        let rows = generate_dated_breakdown(&params).unwrap();
+        let dates: Vec<Date> = rows.iter().map(|row| row.date).collect();
+        assert_eq!(dates, vec![date(2025, 3, 1), date(2026, 3, 1), date(2026, 9, 1)]);
+        assert!((rows[1].result.final_amount - 1102.5).abs() < 1e-9);
+        assert_eq!(rows[2].year, 3);
+    }
+
+    #[test]
+    fn test_reversed_range_is_rejected() {
+        let reversed = count_periods(date(2024, 3, 1), date(2024, 1, 1), Compounding::Monthly);
+        assert!(matches!(reversed, Err(CalcError::InvalidInput(_))));
+
+        let params = DatedParams {
+            principal: 1000.0,
+            annual_rate: 0.05,
+            compounding: Compounding::Monthly,
+            start: date(2024, 3, 1),
+            end: date(2024, 1, 1),
+            day_count: DayCount::ActAct,
+            inflation_rate: 0.0,
+        };
+        assert!(calculate_compound_interest_dated(&params).is_err());
+        assert!(generate_dated_breakdown(&params).is_err());
+
+        let weekly = calculate_weekly_with_yearly_tax_between(1000.0, 0.01, date(2024, 3, 1), date(2024, 1, 1), 10.0, 0.3);
+        assert!(matches!(weekly, Err(CalcError::InvalidInput(_))));
+        let (final_amount, _, _) =
+            calculate_weekly_with_yearly_tax_between(1000.0, 0.01, date(2024, 1, 1), date(2024, 1, 15), 0.0, 0.0).unwrap();
+        assert!((final_amount - 1000.0 * 1.01f64.powi(2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_day_count_sets_partial_period_accrual() {
+        // One whole month, then 15 days of the next
+        let params = DatedParams {
+            principal: 1000.0,
+            annual_rate: 0.06,
+            compounding: Compounding::Monthly,
+            start: date(2024, 1, 1),
+            end: date(2024, 2, 16),
+            day_count: DayCount::Act360,
+            inflation_rate: 0.0,
+        };
+        let act_360 = calculate_compound_interest_dated(&params).unwrap();
+        assert!((act_360.final_amount - 1000.0 * 1.005f64.powf(1.5)).abs() < 1e-9);
+
+        let act_365 = DatedParams { day_count: DayCount::Act365Fixed, ..params.clone() };
+        let act_365 = calculate_compound_interest_dated(&act_365).unwrap();
+        assert!((act_365.final_amount - 1000.0 * 1.005f64.powf(1.0 + 15.0 * 12.0 / 365.0)).abs() < 1e-9);
+
+        // Continuous accrual and the breakdown rows follow the convention too
+        let continuous = DatedParams { compounding: Compounding::Continuous, ..params.clone() };
+        let rows = generate_dated_breakdown(&continuous).unwrap();
+        assert!((rows[0].result.final_amount - 1000.0 * (0.06 * 46.0 / 360.0f64).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_more_than_daily_compounding_matches_undated() {
+        let params = DatedParams {
+            principal: 1000.0,
+            annual_rate: 0.10,
+            compounding: Compounding::PerYear(1000),
+            start: date(2024, 1, 1),
+            end: date(2025, 1, 1),
+            day_count: DayCount::ActAct,
+            inflation_rate: 0.0,
+        };
+        let undated = crate::CompoundInterestParams::new(1000.0, 0.10, Compounding::PerYear(1000), 1.0);
+        let dated = calculate_compound_interest_dated(&params).unwrap();
+        assert!((dated.final_amount - crate::calculate_compound_interest(&undated).final_amount).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+
+use crate::date::{days_in_year, Date};
+
+/// Day-count convention used to turn a pair of dates into a year fraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayCount {
+    /// Actual days divided by a fixed 365-day year
+    Act365Fixed,
+    /// Actual days divided by a 360-day year (money-market basis)
+    Act360,
+    /// 30-day months in a 360-day year (ISDA 30/360 bond basis)
+    Thirty360,
+    /// Actual days, split by calendar year and divided by 365 or 366 (ACT/ACT ISDA)
+    ActAct,
+}
+
+impl DayCount {
+    /// Number of days between two dates under this convention
+    pub fn day_count(&self, start: Date, end: Date) -> i64 {
+        match self {
+            DayCount::Thirty360 => thirty_360_days(start, end),
+            _ => start.days_until(end),
+        }
+    }
+
+    /// Fraction of a year between two dates under this convention
+    /// (negative if `end` is before `start`)
+    pub fn year_fraction(&self, start: Date, end: Date) -> f64 {
+        if end < start {
+            return -self.year_fraction(end, start);
+        }
+        match self {
+            DayCount::Act365Fixed => start.days_until(end) as f64 / 365.0,
+            DayCount::Act360 => start.days_until(end) as f64 / 360.0,
+            DayCount::Thirty360 => thirty_360_days(start, end) as f64 / 360.0,
+            DayCount::ActAct => act_act_fraction(start, end),
+        }
+    }
+}
+
+impl fmt::Display for DayCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DayCount::Act365Fixed => "ACT/365",
+            DayCount::Act360 => "ACT/360",
+            DayCount::Thirty360 => "30/360",
+            DayCount::ActAct => "ACT/ACT",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn thirty_360_days(start: Date, end: Date) -> i64 {
+    let d1 = start.day().min(30);
+    let d2 = if d1 == 30 { end.day().min(30) } else { end.day() };
+    360 * (end.year() - start.year()) as i64
+        + 30 * (end.month() as i64 - start.month() as i64)
+        + (d2 as i64 - d1 as i64)
+}
+
+fn act_act_fraction(start: Date, end: Date) -> f64 {
+    if start.year() == end.year() {
+        return start.days_until(end) as f64 / days_in_year(start.year()) as f64;
+    }
+    let start_year_end = Date::from_ymd(start.year() + 1, 1, 1).unwrap();
+    let end_year_start = Date::from_ymd(end.year(), 1, 1).unwrap();
+    start.days_until(start_year_end) as f64 / days_in_year(start.year()) as f64
+        + (end.year() - start.year() - 1) as f64
+        + end_year_start.days_until(end) as f64 / days_in_year(end.year()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> Date {
+        Date::from_ymd(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_actual_conventions() {
+        let start = date(2024, 1, 15);
+        let end = date(2024, 7, 15);
+        // 182 actual days, including 29 February
+        assert_eq!(DayCount::Act365Fixed.day_count(start, end), 182);
+        assert!((DayCount::Act365Fixed.year_fraction(start, end) - 182.0 / 365.0).abs() < 1e-12);
+        assert!((DayCount::Act360.year_fraction(start, end) - 182.0 / 360.0).abs() < 1e-12);
+        assert!((DayCount::ActAct.year_fraction(start, end) - 182.0 / 366.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_thirty_360() {
+        assert_eq!(DayCount::Thirty360.day_count(date(2024, 1, 31), date(2024, 3, 31)), 60);
+        assert_eq!(DayCount::Thirty360.day_count(date(2024, 1, 15), date(2024, 7, 15)), 180);
+        assert!((DayCount::Thirty360.year_fraction(date(2023, 5, 1), date(2024, 5, 1)) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_act_act_across_years() {
+        // 2023-07-01 to 2024-07-01: 184/365 in 2023 and 182/366 in 2024
+        let fraction = DayCount::ActAct.year_fraction(date(2023, 7, 1), date(2024, 7, 1));
+        assert!((fraction - (184.0 / 365.0 + 182.0 / 366.0)).abs() < 1e-12);
+        let reversed = DayCount::ActAct.year_fraction(date(2024, 7, 1), date(2023, 7, 1));
+        assert!((fraction + reversed).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,540 @@
+//! Exact fixed-point versions of the compound interest calculations.
+//!
+//! Enabled with the `decimal` cargo feature. Interest is posted once per
+//! compounding period and rounded to the cent with a configurable rounding
+//! mode, the same way a bank statement is produced, so results reconcile
+//! exactly instead of drifting by fractions of a cent.
+
+use std::collections::HashMap;
+
+use rust_decimal::prelude::*;
+use rust_decimal::MathematicalOps;
+
+use crate::{Compounding, Contribution, ContributionTiming};
+
+pub use rust_decimal::Decimal;
+
+/// How amounts are rounded to the cent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Round half to even (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Round half away from zero
+    HalfUp,
+    /// Round half toward zero
+    HalfDown,
+    /// Truncate toward zero
+    Down,
+    /// Round away from zero
+    Up,
+    /// Round toward negative infinity
+    Floor,
+    /// Round toward positive infinity
+    Ceiling,
+}
+
+impl RoundingMode {
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfDown => RoundingStrategy::MidpointTowardZero,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+            RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+            RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
+        }
+    }
+
+    /// Round an amount to whole cents
+    pub fn round(self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(2, self.strategy())
+    }
+}
+
+/// Represents the parameters for a decimal compound interest calculation
+#[derive(Debug, Clone)]
+pub struct DecimalParams {
+    /// Initial principal amount
+    pub principal: Decimal,
+    /// Annual interest rate (as a decimal, e.g., 0.05 for 5%)
+    pub annual_rate: Decimal,
+    /// How often interest is compounded and posted
+    pub compounding: Compounding,
+    /// Number of years
+    pub years: Decimal,
+    /// How posted interest is rounded to the cent
+    pub rounding: RoundingMode,
+    /// Expected annual inflation rate used for the real results (0 for none)
+    pub inflation_rate: Decimal,
+}
+
+/// Represents the result of a decimal compound interest calculation
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecimalResult {
+    /// Final amount after compound interest, in whole cents
+    pub final_amount: Decimal,
+    /// Total interest earned, in whole cents
+    pub total_interest: Decimal,
+    /// Initial principal
+    pub principal: Decimal,
+    /// Effective annual rate (not rounded)
+    pub effective_annual_rate: Decimal,
+    /// Final amount in today's money, in whole cents
+    pub real_final_amount: Decimal,
+    /// Gain in purchasing power over the money paid in, in whole cents
+    pub real_total_interest: Decimal,
+    /// Effective annual rate after inflation (not rounded)
+    pub real_effective_annual_rate: Decimal,
+}
+
+fn periods_per_year(compounding: Compounding) -> Option<Decimal> {
+    compounding.periods_per_year().and_then(Decimal::from_f64)
+}
+
+fn effective_annual_rate(annual_rate: Decimal, compounding: Compounding) -> Decimal {
+    match periods_per_year(compounding) {
+        Some(n) => (Decimal::ONE + annual_rate / n).powi(n.to_i64().unwrap_or(1)) - Decimal::ONE,
+        None => annual_rate.exp() - Decimal::ONE,
+    }
+}
+
+/// Grow a balance by posting rounded interest each period for `periods`
+/// periods, with a simple-interest stub for any fractional final period
+fn post_interest(balance: Decimal, periodic_rate: Decimal, periods: Decimal, rounding: RoundingMode) -> Decimal {
+    let whole = periods.floor();
+    let stub = periods - whole;
+    let mut balance = balance;
+
+    for _ in 0..whole.to_u64().unwrap_or(0) {
+        balance = balance.saturating_add(rounding.round(balance.saturating_mul(periodic_rate)));
+    }
+    if stub > Decimal::ZERO {
+        balance = balance.saturating_add(rounding.round(balance.saturating_mul(periodic_rate * stub)));
+    }
+
+    balance
+}
+
+fn grow(principal: Decimal, annual_rate: Decimal, compounding: Compounding, years: Decimal, rounding: RoundingMode) -> Decimal {
+    match periods_per_year(compounding) {
+        Some(n) => post_interest(principal, annual_rate / n, years * n, rounding),
+        None => rounding.round(principal.saturating_mul(crate::Numeric::exp(annual_rate * years))),
+    }
+}
+
+/// Build a result from the final amount and the money paid in to reach it,
+/// both at face value and with each deposit deflated from the date it was made
+fn result(params: &DecimalParams, final_amount: Decimal, paid_in: Decimal, real_paid_in: Decimal) -> DecimalResult {
+    let effective_annual_rate = effective_annual_rate(params.annual_rate, params.compounding);
+    let (real_final_amount, _, real_effective_annual_rate) =
+        crate::real_values(final_amount, real_paid_in, effective_annual_rate, params.inflation_rate, params.years);
+    let real_final_amount = params.rounding.round(real_final_amount);
+
+    DecimalResult {
+        final_amount,
+        total_interest: final_amount - paid_in,
+        principal: params.principal,
+        effective_annual_rate,
+        real_final_amount,
+        real_total_interest: real_final_amount - params.rounding.round(real_paid_in),
+        real_effective_annual_rate,
+    }
+}
+
+/// Calculate compound interest, posting interest rounded to the cent each period
+pub fn calculate_compound_interest(params: &DecimalParams) -> DecimalResult {
+    let principal = params.principal;
+    let final_amount = grow(principal, params.annual_rate, params.compounding, params.years, params.rounding);
+
+    result(params, final_amount, principal, principal)
+}
+
+/// Calculate compound interest with regular contributions
+///
+/// Mirrors the floating-point version: the principal compounds at the chosen
+/// frequency while each contribution is deposited at the beginning or end of
+/// its period and earns the equivalent periodic rate, with interest on both
+/// rounded to the cent. A fractional final period deposits the same fraction of
+/// a contribution and earns simple interest for that fraction, matching the
+/// stub the principal gets.
+pub fn calculate_compound_interest_with_contributions(
+    params: &DecimalParams,
+    contribution: &Contribution<Decimal>,
+) -> DecimalResult {
+    let principal = params.principal;
+    let rounding = params.rounding;
+    let frequency = contribution.frequency.periods_per_year();
+    let periods_per_year = Decimal::from(frequency);
+    let periodic_rate = params.compounding.equivalent_periodic_rate(params.annual_rate, periods_per_year);
+    let periods = params.years * periods_per_year;
+    let total_periods = periods.floor().to_u64().unwrap_or(0);
+    let stub = periods - periods.floor();
+
+    let principal_future_value = grow(principal, params.annual_rate, params.compounding, params.years, rounding);
+
+    // Value of a deposit in today's money, deflated from `period` periods in
+    let deflate = |amount: Decimal, period: Decimal| {
+        amount / Compounding::Annually.growth_factor(params.inflation_rate, period / periods_per_year)
+    };
+    let deposit_offset = match contribution.timing {
+        ContributionTiming::Beginning => Decimal::ZERO,
+        ContributionTiming::End => Decimal::ONE,
+    };
+
+    let mut contribution_future_value = Decimal::ZERO;
+    let mut total_contributions = Decimal::ZERO;
+    let mut real_contributions = Decimal::ZERO;
+    for period in 0..total_periods {
+        let year = (period / frequency as u64) as u32;
+        let periodic_contribution = contribution.escalation.amount_in_year(contribution.amount, year);
+        total_contributions += periodic_contribution;
+        real_contributions += deflate(periodic_contribution, Decimal::from(period) + deposit_offset);
+        match contribution.timing {
+            ContributionTiming::Beginning => {
+                contribution_future_value = contribution_future_value.saturating_add(periodic_contribution);
+                contribution_future_value = contribution_future_value
+                    .saturating_add(rounding.round(contribution_future_value.saturating_mul(periodic_rate)));
+            }
+            ContributionTiming::End => {
+                contribution_future_value = contribution_future_value
+                    .saturating_add(rounding.round(contribution_future_value.saturating_mul(periodic_rate)));
+                contribution_future_value = contribution_future_value.saturating_add(periodic_contribution);
+            }
+        }
+    }
+    if stub > Decimal::ZERO {
+        let year = (total_periods / frequency as u64) as u32;
+        let stub_contribution = rounding.round(contribution.escalation.amount_in_year(contribution.amount, year) * stub);
+        total_contributions += stub_contribution;
+        real_contributions += deflate(stub_contribution, Decimal::from(total_periods) + deposit_offset * stub);
+        match contribution.timing {
+            ContributionTiming::Beginning => {
+                contribution_future_value = contribution_future_value.saturating_add(stub_contribution);
+                contribution_future_value = contribution_future_value
+                    .saturating_add(rounding.round(contribution_future_value.saturating_mul(periodic_rate * stub)));
+            }
+            ContributionTiming::End => {
+                contribution_future_value = contribution_future_value
+                    .saturating_add(rounding.round(contribution_future_value.saturating_mul(periodic_rate * stub)));
+                contribution_future_value = contribution_future_value.saturating_add(stub_contribution);
+            }
+        }
+    }
+
+    let final_amount = principal_future_value.saturating_add(contribution_future_value);
+
+    result(params, final_amount, principal + total_contributions, principal + real_contributions)
+}
+
+/// Calculate the time needed to reach a target amount
+///
+/// Returns the number of years until the posted balance first reaches the
+/// target, which is always a whole number of compounding periods, zero if the
+/// principal already reaches it, or `None` if the target cannot be reached.
+pub fn calculate_time_to_target(
+    principal: Decimal,
+    target_amount: Decimal,
+    annual_rate: Decimal,
+    compounding: Compounding,
+    rounding: RoundingMode,
+) -> Option<Decimal> {
+    if target_amount <= principal {
+        return Some(Decimal::ZERO);
+    }
+    if annual_rate <= Decimal::ZERO || principal <= Decimal::ZERO {
+        return None;
+    }
+
+    let n = match periods_per_year(compounding) {
+        Some(n) => n,
+        None => return Some((target_amount / principal).ln() / annual_rate),
+    };
+    let periodic_rate = annual_rate / n;
+
+    let mut balance = principal;
+    let mut periods = 0u64;
+    while balance < target_amount {
+        let interest = rounding.round(balance * periodic_rate);
+        if interest <= Decimal::ZERO {
+            // Interest rounds away to nothing, so the balance never grows
+            return None;
+        }
+        balance += interest;
+        periods += 1;
+    }
+
+    Some(Decimal::from(periods) / n)
+}
+
+/// Calculate the required principal to reach a target amount in given time,
+/// rounded to the cent
+pub fn calculate_principal_for_target(
+    target_amount: Decimal,
+    annual_rate: Decimal,
+    compounding: Compounding,
+    years: Decimal,
+    rounding: RoundingMode,
+) -> Decimal {
+    if annual_rate <= Decimal::ZERO || years <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+
+    let growth = match periods_per_year(compounding) {
+        Some(n) => crate::Numeric::powf(Decimal::ONE + annual_rate / n, n * years),
+        None => crate::Numeric::exp(annual_rate * years),
+    };
+    rounding.round(target_amount / growth)
+}
+
+/// Generate a year-by-year breakdown of compound interest
+pub fn generate_breakdown(params: &DecimalParams) -> HashMap<u32, DecimalResult> {
+    let mut breakdown = HashMap::new();
+
+    for year in 1..=params.years.floor().to_u32().unwrap_or(0) {
+        let year_params = DecimalParams {
+            years: Decimal::from(year),
+            ..params.clone()
+        };
+        breakdown.insert(year, calculate_compound_interest(&year_params));
+    }
+
+    breakdown
+}
+
+/// Calculate weekly compounding with weekly contributions and yearly capital gains tax
+///
+/// Mirrors `cical::calculate_weekly_with_yearly_tax`, posting each week's return
+/// and each year's tax rounded to the cent. Returns
+/// (final_amount_after_tax, total_interest_before_tax, total_tax_paid).
+pub fn calculate_weekly_with_yearly_tax(
+    principal: Decimal,
+    weekly_rate: Decimal,
+    weeks: u32,
+    weekly_contribution: Decimal,
+    capital_gains_tax: Decimal,
+    rounding: RoundingMode,
+) -> (Decimal, Decimal, Decimal) {
+    let weeks_per_year = 52;
+    let mut current_principal = principal;
+    let mut total_tax_paid = Decimal::ZERO;
+    let mut total_contributions = Decimal::ZERO;
+
+    let mut week = 0;
+    while week < weeks {
+        let weeks_this_year = (weeks - week).min(weeks_per_year);
+        let year_start_principal = current_principal;
+        let year_contributions = weekly_contribution.saturating_mul(Decimal::from(weeks_this_year));
+        total_contributions = total_contributions.saturating_add(year_contributions);
+
+        let mut balance = year_start_principal;
+        for _ in 0..weeks_this_year {
+            balance = balance.saturating_add(rounding.round(balance.saturating_mul(weekly_rate)));
+            balance = balance.saturating_add(weekly_contribution);
+        }
+
+        // Partial years are taxed pro rata, as in the floating-point model
+        let year_profit = balance - year_start_principal - year_contributions;
+        let year_tax = if year_profit > Decimal::ZERO {
+            let fraction = Decimal::from(weeks_this_year) / Decimal::from(weeks_per_year);
+            rounding.round(year_profit * capital_gains_tax * fraction)
+        } else {
+            Decimal::ZERO
+        };
+        total_tax_paid = total_tax_paid.saturating_add(year_tax);
+
+        current_principal = balance - year_tax;
+        week += weeks_this_year;
+    }
+
+    let total_profit_before_tax = current_principal.saturating_add(total_tax_paid) - principal - total_contributions;
+    (current_principal, total_profit_before_tax, total_tax_paid)
+}
+
+/// Format a decimal currency value for display
+pub fn format_currency(amount: Decimal) -> String {
+    crate::format_currency(amount.round_dp(2).to_f64().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_interest_posts_to_the_cent() {
+        let params = DecimalParams {
+            principal: dec("1000.00"),
+            annual_rate: dec("0.05"),
+            compounding: Compounding::Annually,
+            years: dec("10"),
+            rounding: RoundingMode::HalfEven,
+            inflation_rate: Decimal::ZERO,
+        };
+
+        // Posting rounded interest each year lands a cent below the unrounded 1628.89
+        let result = calculate_compound_interest(&params);
+        assert_eq!(result.final_amount, dec("1628.88"));
+        assert_eq!(result.total_interest, dec("628.88"));
+        assert_eq!(result.effective_annual_rate, dec("0.05"));
+    }
+
+    #[test]
+    fn test_real_results_round_to_the_cent() {
+        let params = DecimalParams {
+            principal: dec("1000.00"),
+            annual_rate: dec("0.05"),
+            compounding: Compounding::Annually,
+            years: dec("10"),
+            rounding: RoundingMode::HalfEven,
+            inflation_rate: dec("0.03"),
+        };
+
+        // 1628.88 deflated by 1.03^10
+        let result = calculate_compound_interest(&params);
+        assert_eq!(result.real_final_amount, dec("1212.04"));
+        assert_eq!(result.real_total_interest, dec("212.04"));
+        assert!((result.real_effective_annual_rate - dec("0.02") / dec("1.03")).abs() < dec("0.0000000001"));
+    }
+
+    #[test]
+    fn test_real_interest_deflates_each_contribution() {
+        // 3% compounded monthly just beats 3% inflation on monthly deposits
+        let params = DecimalParams {
+            principal: Decimal::ZERO,
+            annual_rate: dec("0.03"),
+            compounding: Compounding::Monthly,
+            years: dec("10"),
+            rounding: RoundingMode::HalfEven,
+            inflation_rate: dec("0.03"),
+        };
+        let result = calculate_compound_interest_with_contributions(&params, &Contribution::new(dec("100")));
+        assert!((result.real_final_amount - dec("10398.07")).abs() <= dec("0.05"));
+        assert!((result.real_total_interest - dec("21.83")).abs() <= dec("0.05"));
+        assert_eq!(result.real_total_interest.scale(), 2);
+    }
+
+    #[test]
+    fn test_rounding_modes_differ() {
+        let mut params = DecimalParams {
+            principal: dec("1000.00"),
+            annual_rate: dec("0.05"),
+            compounding: Compounding::Monthly,
+            years: dec("1"),
+            rounding: RoundingMode::Down,
+            inflation_rate: Decimal::ZERO,
+        };
+        let truncated = calculate_compound_interest(&params).final_amount;
+        params.rounding = RoundingMode::Up;
+        let rounded_up = calculate_compound_interest(&params).final_amount;
+
+        assert!(truncated < rounded_up);
+        assert_eq!(truncated.scale(), 2);
+    }
+
+    #[test]
+    fn test_weekly_with_tax_matches_float_model() {
+        let (decimal_final, _, decimal_tax) = calculate_weekly_with_yearly_tax(
+            dec("10000"),
+            dec("0.01"),
+            104,
+            dec("100"),
+            dec("0.3"),
+            RoundingMode::HalfEven,
+        );
+        let (float_final, _, float_tax) = crate::calculate_weekly_with_yearly_tax(10000.0, 0.01, 104, 100.0, 0.3);
+
+        assert!((decimal_final.to_f64().unwrap() - float_final).abs() < 1.0);
+        assert!((decimal_tax.to_f64().unwrap() - float_tax).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_generic_core_over_decimal() {
+        let params = crate::CompoundInterestParams::new(dec("1000"), dec("0.05"), Compounding::Annually, dec("10"));
+
+        // The unrounded formula evaluated in decimal arithmetic
+        let result = crate::calculate_compound_interest(&params);
+        assert_eq!(result.final_amount.round_dp(2), dec("1628.89"));
+    }
+
+    #[test]
+    fn test_time_to_target_already_reached() {
+        let years = calculate_time_to_target(dec("1000"), dec("900"), dec("0.05"), Compounding::Monthly, RoundingMode::HalfEven);
+        assert_eq!(years, Some(Decimal::ZERO));
+
+        let unreachable = calculate_time_to_target(dec("1000"), dec("2000"), Decimal::ZERO, Compounding::Monthly, RoundingMode::HalfEven);
+        assert_eq!(unreachable, None);
+    }
+
+    #[test]
+    fn test_contributions_include_final_partial_period() {
+        // Nineteen and a half months of 100 with no interest
+        let params = DecimalParams {
+            principal: Decimal::ZERO,
+            annual_rate: Decimal::ZERO,
+            compounding: Compounding::Monthly,
+            years: dec("1.625"),
+            rounding: RoundingMode::HalfEven,
+            inflation_rate: Decimal::ZERO,
+        };
+        let result = calculate_compound_interest_with_contributions(&params, &Contribution::new(dec("100")));
+        assert_eq!(result.final_amount, dec("1950.00"));
+
+        // The principal and the contributions both earn half a year of simple
+        // interest in the stub: 1100 + 55, and 1000 + 50 + 500
+        let params = DecimalParams {
+            principal: dec("1000"),
+            annual_rate: dec("0.10"),
+            compounding: Compounding::Annually,
+            years: dec("1.5"),
+            ..params
+        };
+        let contribution = Contribution {
+            frequency: crate::PaymentFrequency::Annually,
+            ..Contribution::new(dec("1000"))
+        };
+        let result = calculate_compound_interest_with_contributions(&params, &contribution);
+        assert_eq!(result.final_amount, dec("2705.00"));
+        assert_eq!(result.total_interest, dec("205.00"));
+    }
+
+    #[test]
+    fn test_overflow_saturates_instead_of_panicking() {
+        // 1000 * 1.5^200 is far beyond the largest Decimal
+        let params = crate::CompoundInterestParams::new(dec("1000"), dec("0.5"), Compounding::Annually, dec("200"));
+        assert_eq!(crate::calculate_compound_interest(&params).final_amount, Decimal::MAX);
+        let with_contributions = crate::calculate_compound_interest_with_contributions(&params, &Contribution::new(dec("100")));
+        assert_eq!(with_contributions.final_amount, Decimal::MAX);
+
+        let params = DecimalParams {
+            principal: dec("1000"),
+            annual_rate: dec("0.5"),
+            compounding: Compounding::Annually,
+            years: dec("200"),
+            rounding: RoundingMode::HalfEven,
+            inflation_rate: Decimal::ZERO,
+        };
+        assert_eq!(calculate_compound_interest(&params).final_amount, Decimal::MAX);
+        assert_eq!(crate::Numeric::ln(Decimal::ZERO), Decimal::MIN);
+        assert_eq!(crate::Numeric::exp(dec("-100")), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_weekly_with_tax_overflow_saturates() {
+        // 1% a week for 200 years is far beyond the largest Decimal
+        let (final_amount, _, total_tax) = calculate_weekly_with_yearly_tax(
+            dec("10000"),
+            dec("0.01"),
+            52 * 200,
+            dec("100"),
+            Decimal::ZERO,
+            RoundingMode::HalfEven,
+        );
+        assert_eq!(final_amount, Decimal::MAX);
+        assert_eq!(total_tax, Decimal::ZERO);
+    }
+}
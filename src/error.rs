@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// Error returned when a calculation cannot produce a meaningful answer
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    /// The target can never be reached with the given inputs
+    Unreachable,
+    /// The numeric solver did not settle on an answer
+    NoConvergence,
+    /// An input is outside the range the calculation supports
+    InvalidInput(String),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::Unreachable => write!(f, "the target cannot be reached with the given parameters"),
+            CalcError::NoConvergence => write!(f, "the solver did not converge"),
+            CalcError::InvalidInput(message) => write!(f, "invalid input: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
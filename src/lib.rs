@@ -1,29 +1,475 @@
 use std::collections::HashMap;
+use std::fmt;
+
+pub mod accumulation;
+pub mod backtest;
+pub mod cashflow;
+pub mod date;
+pub mod dated;
+pub mod daycount;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod error;
+pub mod loan;
+pub mod numeric;
+mod random;
+pub mod refinance;
+pub mod simulation;
+mod solver;
+pub mod spreadsheet;
+pub mod tvm;
+pub mod withdrawal;
+
+pub use accumulation::{accumulate, AccumulationPlan, AccumulationResult};
+pub use backtest::{parse_returns, rolling_backtest, BacktestWindow, PeriodReturn, RollingBacktest};
+pub use cashflow::{irr, npv, parse_cash_flows, xirr, xnpv, CashFlow};
+pub use date::Date;
+pub use dated::{
+    calculate_compound_interest_dated, calculate_weekly_with_yearly_tax_between, count_periods,
+    generate_dated_breakdown, DatedBreakdownRow, DatedParams, PeriodCount,
+};
+pub use daycount::DayCount;
+pub use error::CalcError;
+pub use loan::{
+    amortization_schedule, AmortizationRow, AmortizationSchedule, AmortizationYear, ExtraPayment, Loan, RateAdjustment,
+};
+pub use numeric::Numeric;
+pub use refinance::{compare_refinance, RefinanceComparison};
+pub use simulation::{
+    bootstrap, simulate, Bootstrap, MonteCarlo, PercentileReport, Resampling, ReturnDistribution, SimulationReport,
+};
+pub use tvm::{solve_tvm, Tvm, TvmSolution, TvmUnknown};
+pub use withdrawal::{
+    analyze_sequence_risk, calculate_withdrawals, calculate_withdrawals_with_returns, reorder_returns, ReturnSequence,
+    SequenceOutcome, SequenceRisk, SequenceYear, WithdrawalPlan, WithdrawalResult, WithdrawalRow, WithdrawalYear,
+};
+
+/// How often interest is compounded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compounding {
+    /// Once per year
+    Annually,
+    /// Twice per year
+    Semiannually,
+    /// Four times per year
+    Quarterly,
+    /// Twelve times per year
+    Monthly,
+    /// 52 times per year
+    Weekly,
+    /// 365 times per year
+    Daily,
+    /// A custom number of periods per year (0 is treated as continuous)
+    PerYear(u32),
+    /// Continuous compounding, A = Pe^(rt)
+    Continuous,
+}
+
+impl Compounding {
+    /// Number of compounding periods per year, or `None` for continuous compounding
+    pub fn periods_per_year(&self) -> Option<f64> {
+        match self {
+            Compounding::Annually => Some(1.0),
+            Compounding::Semiannually => Some(2.0),
+            Compounding::Quarterly => Some(4.0),
+            Compounding::Monthly => Some(12.0),
+            Compounding::Weekly => Some(52.0),
+            Compounding::Daily => Some(365.0),
+            Compounding::PerYear(0) | Compounding::Continuous => None,
+            Compounding::PerYear(n) => Some(*n as f64),
+        }
+    }
+
+    /// Growth factor applied to a balance over `years` at `annual_rate`
+    pub fn growth_factor<T: Numeric>(&self, annual_rate: T, years: T) -> T {
+        match self.periods_per_year() {
+            Some(n) => {
+                let n = T::from_f64(n);
+                (T::one() + annual_rate / n).powf(n * years)
+            }
+            None => (annual_rate * years).exp(),
+        }
+    }
+
+    /// Effective annual rate for a nominal `annual_rate`
+    pub fn effective_annual_rate<T: Numeric>(&self, annual_rate: T) -> T {
+        self.growth_factor(annual_rate, T::one()) - T::one()
+    }
+
+    /// Rate per payment period that grows a balance exactly as this compounding
+    /// does, for payments made `periods_per_year` times a year
+    pub fn equivalent_periodic_rate<T: Numeric>(&self, annual_rate: T, periods_per_year: T) -> T {
+        self.growth_factor(annual_rate, T::one() / periods_per_year) - T::one()
+    }
+}
+
+impl From<u32> for Compounding {
+    /// Map a number of periods per year to a frequency, with 0 meaning continuous
+    fn from(periods_per_year: u32) -> Self {
+        match periods_per_year {
+            0 => Compounding::Continuous,
+            1 => Compounding::Annually,
+            2 => Compounding::Semiannually,
+            4 => Compounding::Quarterly,
+            12 => Compounding::Monthly,
+            52 => Compounding::Weekly,
+            365 => Compounding::Daily,
+            n => Compounding::PerYear(n),
+        }
+    }
+}
+
+impl fmt::Display for Compounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.periods_per_year() {
+            Some(1.0) => write!(f, "1 time per year"),
+            Some(n) => write!(f, "{} times per year", n),
+            None => write!(f, "continuous"),
+        }
+    }
+}
 
 /// Represents the parameters for compound interest calculations
 #[derive(Debug, Clone)]
-pub struct CompoundInterestParams {
+pub struct CompoundInterestParams<T = f64> {
     /// Initial principal amount
-    pub principal: f64,
+    pub principal: T,
     /// Annual interest rate (as a decimal, e.g., 0.05 for 5%)
-    pub annual_rate: f64,
-    /// Number of times interest is compounded per year
-    pub compounds_per_year: u32,
+    pub annual_rate: T,
+    /// How often interest is compounded
+    pub compounding: Compounding,
     /// Number of years
-    pub years: f64,
+    pub years: T,
+    /// Expected annual inflation rate used for the real results (0 for none)
+    pub inflation_rate: T,
+    /// Later changes to the annual rate, which applies until the first of them
+    /// (empty for a constant rate)
+    pub rate_schedule: Vec<RateSegment<T>>,
+}
+
+/// A change in the annual interest rate partway through the horizon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateSegment<T = f64> {
+    /// Compounding period the rate takes effect from, counting the first as 0
+    /// (years for continuous compounding)
+    pub start_period: u32,
+    /// Annual interest rate from that period on (as a decimal)
+    pub annual_rate: T,
+}
+
+impl<T: Numeric> CompoundInterestParams<T> {
+    /// Parameters with no inflation adjustment and a constant rate
+    pub fn new(principal: T, annual_rate: T, compounding: Compounding, years: T) -> Self {
+        CompoundInterestParams {
+            principal,
+            annual_rate,
+            compounding,
+            years,
+            inflation_rate: T::zero(),
+            rate_schedule: Vec::new(),
+        }
+    }
+    
+    /// Number of schedule periods per year: the compounding periods, or whole
+    /// years for continuous compounding
+    fn schedule_periods_per_year(&self) -> T {
+        T::from_f64(self.compounding.periods_per_year().unwrap_or(1.0))
+    }
+    
+    /// Annual rate in force during the given compounding period
+    pub fn rate_in_period(&self, period: u32) -> T {
+        self.rate_schedule
+            .iter()
+            .filter(|segment| segment.start_period <= period)
+            .max_by_key(|segment| segment.start_period)
+            .map_or(self.annual_rate, |segment| segment.annual_rate)
+    }
+    
+    /// Growth factor from `from` to `to` years after the start, with each
+    /// stretch compounding at the rate the schedule sets for it
+    fn growth_between(&self, from: T, to: T) -> T {
+        let periods_per_year = self.schedule_periods_per_year();
+        let mut changes: Vec<(T, T)> = self
+            .rate_schedule
+            .iter()
+            .map(|segment| (T::from_f64(segment.start_period as f64) / periods_per_year, segment.annual_rate))
+            .collect();
+        changes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        
+        let mut rate = self.annual_rate;
+        let mut time = from;
+        let mut growth = T::one();
+        for (start, next_rate) in changes {
+            if start >= to {
+                break;
+            }
+            if start > time {
+                growth = growth.saturating_mul(self.compounding.growth_factor(rate, start - time));
+                time = start;
+            }
+            rate = next_rate;
+        }
+        growth.saturating_mul(self.compounding.growth_factor(rate, to - time))
+    }
+    
+    /// Effective annual rate, averaged over the horizon when the rate changes
+    fn average_effective_annual_rate(&self) -> T {
+        if self.rate_schedule.is_empty() || self.years <= T::zero() {
+            return self.compounding.effective_annual_rate(self.annual_rate);
+        }
+        self.growth_between(T::zero(), self.years).powf(T::one() / self.years) - T::one()
+    }
+    
+    /// The same parameters for a horizon starting `periods` compounding
+    /// periods after this one, with the schedule moved along to match
+    fn starting_after(&self, periods: u32) -> Self {
+        CompoundInterestParams {
+            annual_rate: self.rate_in_period(periods),
+            rate_schedule: self
+                .rate_schedule
+                .iter()
+                .filter(|segment| segment.start_period > periods)
+                .map(|segment| RateSegment {
+                    start_period: segment.start_period - periods,
+                    ..*segment
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl CompoundInterestParams {
+    /// Build parameters that accrue interest from `start` to `end`, measuring
+    /// the elapsed time with the given day-count convention
+    pub fn between_dates(
+        principal: f64,
+        annual_rate: f64,
+        compounding: Compounding,
+        start: Date,
+        end: Date,
+        day_count: DayCount,
+    ) -> Self {
+        CompoundInterestParams::new(principal, annual_rate, compounding, day_count.year_fraction(start, end))
+    }
+}
+
+/// How often regular payments such as contributions are made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaymentFrequency {
+    /// 52 times per year
+    Weekly,
+    /// Every two weeks, 26 times per year
+    Biweekly,
+    /// Twice a month, 24 times per year
+    Semimonthly,
+    /// 12 times per year
+    #[default]
+    Monthly,
+    /// 4 times per year
+    Quarterly,
+    /// Once per year
+    Annually,
+}
+
+impl PaymentFrequency {
+    /// Number of payments per year
+    pub fn periods_per_year(&self) -> u32 {
+        match self {
+            PaymentFrequency::Weekly => 52,
+            PaymentFrequency::Biweekly => 26,
+            PaymentFrequency::Semimonthly => 24,
+            PaymentFrequency::Monthly => 12,
+            PaymentFrequency::Quarterly => 4,
+            PaymentFrequency::Annually => 1,
+        }
+    }
+
+    /// Frequency with the given number of payments per year, if there is one
+    pub fn from_periods_per_year(periods_per_year: u32) -> Option<Self> {
+        match periods_per_year {
+            52 => Some(PaymentFrequency::Weekly),
+            26 => Some(PaymentFrequency::Biweekly),
+            24 => Some(PaymentFrequency::Semimonthly),
+            12 => Some(PaymentFrequency::Monthly),
+            4 => Some(PaymentFrequency::Quarterly),
+            1 => Some(PaymentFrequency::Annually),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PaymentFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PaymentFrequency::Weekly => "weekly",
+            PaymentFrequency::Biweekly => "biweekly",
+            PaymentFrequency::Semimonthly => "semimonthly",
+            PaymentFrequency::Monthly => "monthly",
+            PaymentFrequency::Quarterly => "quarterly",
+            PaymentFrequency::Annually => "annually",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// When regular contributions are deposited within each period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContributionTiming {
+    /// At the start of each period (annuity due)
+    Beginning,
+    /// At the end of each period (ordinary annuity)
+    #[default]
+    End,
+}
+
+/// How a regular contribution grows from one year to the next
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Escalation<T = f64> {
+    /// The contribution stays the same every year
+    None,
+    /// The contribution grows by a percentage each year (as a decimal, e.g., 0.03 for 3%)
+    Percent(T),
+    /// The contribution grows by a fixed amount each year
+    Step(T),
+}
+
+impl<T: Numeric> Escalation<T> {
+    /// Contribution per period during the given year, counting the first year as 0
+    pub fn amount_in_year(&self, base: T, year: u32) -> T {
+        let year = T::from_f64(year as f64);
+        match self {
+            Escalation::None => base,
+            Escalation::Percent(rate) => base.saturating_mul((T::one() + *rate).powf(year)),
+            Escalation::Step(step) => base + *step * year,
+        }
+    }
+}
+
+/// A regular contribution made alongside the initial principal
+#[derive(Debug, Clone)]
+pub struct Contribution<T = f64> {
+    /// Amount contributed each period during the first year
+    pub amount: T,
+    /// How often contributions are made
+    pub frequency: PaymentFrequency,
+    /// Whether each contribution arrives at the beginning or end of its period
+    pub timing: ContributionTiming,
+    /// How the amount grows each year
+    pub escalation: Escalation<T>,
+}
+
+impl<T> Contribution<T> {
+    /// A level monthly contribution deposited at the end of each month
+    pub fn new(amount: T) -> Self {
+        Contribution {
+            amount,
+            frequency: PaymentFrequency::Monthly,
+            timing: ContributionTiming::End,
+            escalation: Escalation::None,
+        }
+    }
 }
 
 /// Represents the result of a compound interest calculation
 #[derive(Debug, Clone)]
-pub struct CompoundInterestResult {
+pub struct CompoundInterestResult<T = f64> {
     /// Final amount after compound interest
-    pub final_amount: f64,
+    pub final_amount: T,
     /// Total interest earned
-    pub total_interest: f64,
+    pub total_interest: T,
     /// Initial principal
-    pub principal: f64,
+    pub principal: T,
     /// Effective annual rate
-    pub effective_annual_rate: f64,
+    pub effective_annual_rate: T,
+    /// Final amount in today's money, after inflation
+    pub real_final_amount: T,
+    /// Gain in purchasing power over the money paid in, with each deposit
+    /// deflated from the date it was made
+    pub real_total_interest: T,
+    /// Effective annual rate after inflation
+    pub real_effective_annual_rate: T,
+}
+
+/// One year of a breakdown that includes regular contributions
+#[derive(Debug, Clone)]
+pub struct ContributionBreakdownRow<T = f64> {
+    /// Year number, starting at 1
+    pub year: u32,
+    /// Amount contributed during this year
+    pub contributions: T,
+    /// Amount contributed from the start up to the end of this year
+    pub total_contributions: T,
+    /// Balance at the end of this year
+    pub final_amount: T,
+    /// Interest earned from the start up to the end of this year
+    pub total_interest: T,
+    /// Balance at the end of this year in today's money
+    pub real_final_amount: T,
+    /// Gain in purchasing power over the money paid in up to the end of this year
+    pub real_total_interest: T,
+}
+
+/// Future value of a level series of payments over `periods` periods
+fn annuity_future_value<T: Numeric>(payment: T, periodic_rate: T, periods: T, timing: ContributionTiming) -> T {
+    let ordinary = if periodic_rate != T::zero() {
+        let growth = (T::one() + periodic_rate).powf(periods) - T::one();
+        payment.saturating_mul(growth.saturating_mul(T::one() / periodic_rate))
+    } else {
+        payment * periods
+    };
+    match timing {
+        ContributionTiming::Beginning => ordinary.saturating_mul(T::one() + periodic_rate),
+        ContributionTiming::End => ordinary,
+    }
+}
+
+/// Future value at the end of the horizon of one year's contributions under a
+/// rate schedule
+///
+/// Each deposit is grown on its own through the rates in force after it is
+/// made. A final fractional period deposits the same fraction of a payment.
+fn scheduled_block_future_value<T: Numeric>(
+    params: &CompoundInterestParams<T>,
+    payment: T,
+    periods_per_year: T,
+    year: u32,
+    periods: T,
+    timing: ContributionTiming,
+) -> T {
+    let first_period = T::from_f64(year as f64) * periods_per_year;
+    let mut future_value = T::zero();
+    let mut period = T::zero();
+    while period < periods {
+        let fraction = if periods - period < T::one() { periods - period } else { T::one() };
+        let deposited = match timing {
+            ContributionTiming::Beginning => first_period + period,
+            ContributionTiming::End => first_period + period + fraction,
+        };
+        let growth = params.growth_between(deposited / periods_per_year, params.years);
+        future_value = future_value.saturating_add((payment * fraction).saturating_mul(growth));
+        period = period + T::one();
+    }
+    future_value
+}
+
+/// Inflation-adjusted final amount, gain over the money paid in, and effective
+/// annual rate
+///
+/// The final amount is deflated to today's money at the inflation rate over the
+/// horizon, and the real rate follows the Fisher relation (1 + r) / (1 + i) - 1.
+/// `real_paid_in` is the money paid in with each deposit already deflated from
+/// the date it was made.
+pub(crate) fn real_values<T: Numeric>(
+    final_amount: T,
+    real_paid_in: T,
+    effective_annual_rate: T,
+    inflation_rate: T,
+    years: T,
+) -> (T, T, T) {
+    let real_final_amount = final_amount / Compounding::Annually.growth_factor(inflation_rate, years);
+    let real_effective_annual_rate = (T::one() + effective_annual_rate) / (T::one() + inflation_rate) - T::one();
+    (real_final_amount, real_final_amount - real_paid_in, real_effective_annual_rate)
 }
 
 /// Calculate compound interest using the standard formula
@@ -34,56 +480,102 @@ pub struct CompoundInterestResult {
 /// r = Annual interest rate
 /// n = Number of times interest is compounded per year
 /// t = Time in years
-pub fn calculate_compound_interest(params: &CompoundInterestParams) -> CompoundInterestResult {
+///
+/// With continuous compounding this becomes A = Pe^(rt). With a rate schedule
+/// the growth of each stretch at its own rate is multiplied together.
+pub fn calculate_compound_interest<T: Numeric>(params: &CompoundInterestParams<T>) -> CompoundInterestResult<T> {
     let principal = params.principal;
-    let rate = params.annual_rate;
-    let compounds = params.compounds_per_year as f64;
     let years = params.years;
     
-    let final_amount = principal * (1.0 + rate / compounds).powf(compounds * years);
+    let final_amount = principal.saturating_mul(params.growth_between(T::zero(), years));
     let total_interest = final_amount - principal;
-    let effective_annual_rate = (1.0 + rate / compounds).powf(compounds) - 1.0;
+    let effective_annual_rate = params.average_effective_annual_rate();
+    let (real_final_amount, real_total_interest, real_effective_annual_rate) =
+        real_values(final_amount, principal, effective_annual_rate, params.inflation_rate, years);
     
     CompoundInterestResult {
         final_amount,
         total_interest,
         principal,
         effective_annual_rate,
+        real_final_amount,
+        real_total_interest,
+        real_effective_annual_rate,
     }
 }
 
 /// Calculate compound interest with regular contributions
-/// This uses the future value of annuity formula combined with compound interest
-pub fn calculate_compound_interest_with_contributions(
-    params: &CompoundInterestParams,
-    monthly_contribution: f64,
-) -> CompoundInterestResult {
+/// This uses the future value of annuity formula combined with compound interest.
+/// Contributions grow at the rate per contribution period that is equivalent to
+/// the compounding frequency, so the principal and contributions earn the same
+/// effective annual rate. Contributions at the beginning of each period
+/// (annuity due) earn one extra period of interest compared with contributions
+/// at the end. An escalating contribution is treated as a growing annuity, with
+/// each year's payments valued as a block and grown to the end of the horizon.
+/// Under a rate schedule each payment is instead grown through the rates in
+/// force after it is deposited.
+pub fn calculate_compound_interest_with_contributions<T: Numeric>(
+    params: &CompoundInterestParams<T>,
+    contribution: &Contribution<T>,
+) -> CompoundInterestResult<T> {
     let principal = params.principal;
     let rate = params.annual_rate;
-    let compounds = params.compounds_per_year as f64;
     let years = params.years;
-    let monthly_rate = rate / 12.0;
-    let total_months = years * 12.0;
+    let periods_per_year = T::from_f64(contribution.frequency.periods_per_year() as f64);
+    let periodic_rate = params.compounding.equivalent_periodic_rate(rate, periods_per_year);
+    let periodic_inflation = Compounding::Annually.equivalent_periodic_rate(params.inflation_rate, periods_per_year);
+    let total_periods = years * periods_per_year;
     
     // Future value of initial principal
-    let principal_future_value = principal * (1.0 + rate / compounds).powf(compounds * years);
+    let principal_future_value = principal.saturating_mul(params.growth_between(T::zero(), years));
     
-    // Future value of monthly contributions (annuity)
-    let contribution_future_value = if monthly_rate > 0.0 {
-        monthly_contribution * ((1.0 + monthly_rate).powf(total_months) - 1.0) / monthly_rate
-    } else {
-        monthly_contribution * total_months
-    };
+    // Future value of periodic contributions (annuity), one year at a time
+    let mut contribution_future_value = T::zero();
+    let mut total_contributions = T::zero();
+    // Contributions grown at the inflation rate, which deflate back to their
+    // value in today's money from the date each was made
+    let mut inflated_contributions = T::zero();
+    let mut remaining_periods = total_periods;
+    let mut year = 0;
+    while remaining_periods > T::zero() {
+        let block = if remaining_periods < periods_per_year { remaining_periods } else { periods_per_year };
+        remaining_periods = remaining_periods - block;
+        
+        let periodic_contribution = contribution.escalation.amount_in_year(contribution.amount, year);
+        let block_future_value = if params.rate_schedule.is_empty() {
+            let block_future_value = annuity_future_value(periodic_contribution, periodic_rate, block, contribution.timing);
+            block_future_value.saturating_mul((T::one() + periodic_rate).powf(remaining_periods))
+        } else {
+            scheduled_block_future_value(params, periodic_contribution, periods_per_year, year, block, contribution.timing)
+        };
+        contribution_future_value = contribution_future_value.saturating_add(block_future_value);
+        total_contributions = total_contributions + periodic_contribution * block;
+        let inflated = annuity_future_value(periodic_contribution, periodic_inflation, block, contribution.timing)
+            .saturating_mul((T::one() + periodic_inflation).powf(remaining_periods));
+        inflated_contributions = inflated_contributions.saturating_add(inflated);
+        year += 1;
+    }
     
-    let final_amount = principal_future_value + contribution_future_value;
-    let total_interest = final_amount - principal - (monthly_contribution * total_months);
-    let effective_annual_rate = (1.0 + rate / compounds).powf(compounds) - 1.0;
+    let final_amount = principal_future_value.saturating_add(contribution_future_value);
+    let total_interest = final_amount - principal - total_contributions;
+    let effective_annual_rate = params.average_effective_annual_rate();
+    let real_contributions = inflated_contributions / Compounding::Annually.growth_factor(params.inflation_rate, years);
+    let (real_final_amount, real_total_interest, real_effective_annual_rate) = real_values(
+        final_amount,
+        principal + real_contributions,
+        effective_annual_rate,
+        params.inflation_rate,
+        years,
+    );
     
     CompoundInterestResult {
         final_amount,
         total_interest,
         principal,
         effective_annual_rate,
+        real_final_amount,
+        real_total_interest,
+        real_effective_annual_rate,
     }
 }
 
@@ -92,44 +584,281 @@ pub fn calculate_time_to_target(
     principal: f64,
     target_amount: f64,
     annual_rate: f64,
-    compounds_per_year: u32,
+    compounding: Compounding,
 ) -> f64 {
     let rate = annual_rate;
-    let compounds = compounds_per_year as f64;
     
     if rate <= 0.0 || principal <= 0.0 || target_amount <= principal {
         return 0.0;
     }
     
-    let years = (target_amount / principal).ln() / (compounds * (1.0 + rate / compounds).ln());
-    years
+    // ln(A/P) divided by the log growth over one year
+    (target_amount / principal).ln() / compounding.growth_factor(rate, 1.0).ln()
 }
 
-/// Calculate the required principal to reach a target amount in given time
-pub fn calculate_principal_for_target(
+/// Longest horizon the time-to-target solvers search before giving up
+const MAX_YEARS_TO_TARGET: f64 = 1000.0;
+
+/// Balance after `years` of growth with regular contributions, optionally
+/// paying capital gains tax on each year's profit the way
+/// `calculate_weekly_with_yearly_tax` does
+fn balance_after_years(
+    params: &CompoundInterestParams,
+    contribution: &Contribution,
+    capital_gains_tax: Option<f64>,
+) -> f64 {
+    let tax_rate = match capital_gains_tax {
+        Some(tax_rate) => tax_rate,
+        None => return calculate_compound_interest_with_contributions(params, contribution).final_amount,
+    };
+    
+    let mut balance = params.principal;
+    let mut remaining_years = params.years;
+    let mut year = 0;
+    while remaining_years > 0.0 {
+        let fraction = remaining_years.min(1.0);
+        remaining_years -= fraction;
+        
+        let elapsed_periods = (year as f64 * params.compounding.periods_per_year().unwrap_or(1.0)) as u32;
+        let year_params = CompoundInterestParams {
+            principal: balance,
+            years: fraction,
+            ..params.starting_after(elapsed_periods)
+        };
+        let year_contribution = Contribution {
+            amount: contribution.escalation.amount_in_year(contribution.amount, year),
+            escalation: Escalation::None,
+            ..contribution.clone()
+        };
+        let result = calculate_compound_interest_with_contributions(&year_params, &year_contribution);
+        
+        // Partial years are taxed pro rata, as in the weekly model
+        let year_profit = result.total_interest;
+        let year_tax = if year_profit > 0.0 { year_profit * tax_rate * fraction } else { 0.0 };
+        balance = result.final_amount - year_tax;
+        year += 1;
+    }
+    
+    balance
+}
+
+/// Calculate the time needed to reach a target amount with regular contributions
+///
+/// Optionally applies yearly capital gains tax to each year's profit, as in
+/// `calculate_weekly_with_yearly_tax`. The time is found numerically and
+/// returned in years; `CalcError::Unreachable` is returned if the balance
+/// never reaches the target.
+pub fn calculate_time_to_target_with_contributions(
+    principal: f64,
     target_amount: f64,
     annual_rate: f64,
-    compounds_per_year: u32,
-    years: f64,
-) -> f64 {
+    compounding: Compounding,
+    contribution: &Contribution,
+    capital_gains_tax: Option<f64>,
+) -> Result<f64, CalcError> {
+    let params = CompoundInterestParams::new(principal, annual_rate, compounding, 0.0);
+    calculate_time_to_target_with_schedule(&params, target_amount, contribution, capital_gains_tax)
+}
+
+/// Calculate the time needed to reach a target amount when the rate follows
+/// `params.rate_schedule`
+///
+/// Works like `calculate_time_to_target_with_contributions`, starting from
+/// `params.principal` and `params.annual_rate`; `params.years` is ignored.
+pub fn calculate_time_to_target_with_schedule(
+    params: &CompoundInterestParams,
+    target_amount: f64,
+    contribution: &Contribution,
+    capital_gains_tax: Option<f64>,
+) -> Result<f64, CalcError> {
+    if contribution.amount < 0.0 {
+        return Err(CalcError::InvalidInput("contributions must not be negative".to_string()));
+    }
+    if let Some(tax_rate) = capital_gains_tax {
+        if !(0.0..1.0).contains(&tax_rate) {
+            return Err(CalcError::InvalidInput("capital gains tax must be at least 0 and below 1".to_string()));
+        }
+    }
+    if target_amount <= params.principal {
+        return Ok(0.0);
+    }
+    
+    let balance_at = |years: f64| {
+        let params = CompoundInterestParams {
+            years,
+            ..params.clone()
+        };
+        balance_after_years(&params, contribution, capital_gains_tax)
+    };
+    solver::solve_increasing(|years| balance_at(years) - target_amount, 1.0, MAX_YEARS_TO_TARGET, 1e-9)
+        .ok_or(CalcError::Unreachable)
+}
+
+/// Calculate the number of whole weeks needed to reach a target amount under
+/// the weekly compounding and yearly tax model of `calculate_weekly_with_yearly_tax`
+pub fn calculate_weeks_to_target_with_yearly_tax(
+    principal: f64,
+    target_amount: f64,
+    weekly_rate: f64,
+    weekly_contribution: f64,
+    capital_gains_tax: f64,
+) -> Result<u32, CalcError> {
+    if weekly_contribution < 0.0 {
+        return Err(CalcError::InvalidInput("contributions must not be negative".to_string()));
+    }
+    if target_amount <= principal {
+        return Ok(0);
+    }
+    
+    let max_weeks = (MAX_YEARS_TO_TARGET * 52.0) as u32;
+    let reached = |weeks: u32| {
+        let (final_after_tax, _, _) =
+            calculate_weekly_with_yearly_tax(principal, weekly_rate, weeks, weekly_contribution, capital_gains_tax);
+        final_after_tax >= target_amount
+    };
+    
+    let mut low = 0;
+    let mut high = 52;
+    while !reached(high) {
+        if high >= max_weeks {
+            return Err(CalcError::Unreachable);
+        }
+        low = high;
+        high = (high * 2).min(max_weeks);
+    }
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        if reached(mid) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    
+    Ok(high)
+}
+
+/// Calculate the required principal to reach a target amount in given time
+pub fn calculate_principal_for_target<T: Numeric>(
+    target_amount: T,
+    annual_rate: T,
+    compounding: Compounding,
+    years: T,
+) -> T {
     let rate = annual_rate;
-    let compounds = compounds_per_year as f64;
     
-    if rate <= 0.0 || years <= 0.0 {
-        return 0.0;
+    if rate <= T::zero() || years <= T::zero() {
+        return T::zero();
+    }
+    
+    target_amount / compounding.growth_factor(rate, years)
+}
+
+/// Calculate the regular contribution needed to reach a target amount
+///
+/// `params.principal` is the starting balance, which may be zero. The final
+/// balance grows linearly with the contribution, so the amount is found
+/// directly from the future value of a contribution of one. Returns zero if
+/// the principal alone already reaches the target.
+pub fn calculate_contribution_for_target<T: Numeric>(
+    params: &CompoundInterestParams<T>,
+    target_amount: T,
+    frequency: PaymentFrequency,
+    timing: ContributionTiming,
+) -> Result<T, CalcError> {
+    if params.years <= T::zero() {
+        return Err(CalcError::InvalidInput("the time period must be positive".to_string()));
+    }
+    
+    let shortfall = target_amount - calculate_compound_interest(params).final_amount;
+    if shortfall <= T::zero() {
+        return Ok(T::zero());
     }
     
-    let principal = target_amount / (1.0 + rate / compounds).powf(compounds * years);
-    principal
+    let unit_params = CompoundInterestParams {
+        principal: T::zero(),
+        ..params.clone()
+    };
+    let unit_contribution = Contribution {
+        amount: T::one(),
+        frequency,
+        timing,
+        escalation: Escalation::None,
+    };
+    let unit_value = calculate_compound_interest_with_contributions(&unit_params, &unit_contribution).final_amount;
+    if unit_value <= T::zero() {
+        return Err(CalcError::Unreachable);
+    }
+    
+    Ok(shortfall / unit_value)
+}
+
+/// Lowest rate the rate solvers consider, a 99% loss per period
+const MIN_SOLVED_RATE: f64 = -0.99;
+
+/// Highest rate the rate solvers consider before giving up
+const MAX_SOLVED_RATE: f64 = 1000.0;
+
+/// Calculate the annual rate needed to grow a principal and regular
+/// contributions into a target amount over `years`
+///
+/// The rate is found numerically and may be negative if the target is less
+/// than the amount paid in. Use `Contribution::new(0.0)` for no contributions.
+pub fn calculate_rate_for_target(
+    principal: f64,
+    target_amount: f64,
+    compounding: Compounding,
+    years: f64,
+    contribution: &Contribution,
+) -> Result<f64, CalcError> {
+    if years <= 0.0 {
+        return Err(CalcError::InvalidInput("the time period must be positive".to_string()));
+    }
+    if target_amount <= 0.0 {
+        return Err(CalcError::InvalidInput("the target amount must be positive".to_string()));
+    }
+    
+    let balance_at = |annual_rate: f64| {
+        let params = CompoundInterestParams::new(principal, annual_rate, compounding, years);
+        calculate_compound_interest_with_contributions(&params, contribution).final_amount
+    };
+    solver::find_root_increasing(|rate| balance_at(rate) - target_amount, MIN_SOLVED_RATE, 1.0, MAX_SOLVED_RATE, 1e-12)
+}
+
+/// Calculate the weekly rate needed to reach a target amount after tax under
+/// the weekly compounding and yearly tax model of `calculate_weekly_with_yearly_tax`
+pub fn calculate_weekly_rate_for_target(
+    principal: f64,
+    target_amount: f64,
+    weeks: u32,
+    weekly_contribution: f64,
+    capital_gains_tax: f64,
+) -> Result<f64, CalcError> {
+    if weeks == 0 {
+        return Err(CalcError::InvalidInput("the number of weeks must be positive".to_string()));
+    }
+    if target_amount <= 0.0 {
+        return Err(CalcError::InvalidInput("the target amount must be positive".to_string()));
+    }
+    if !(0.0..1.0).contains(&capital_gains_tax) {
+        return Err(CalcError::InvalidInput("capital gains tax must be at least 0 and below 1".to_string()));
+    }
+    
+    let balance_at = |weekly_rate: f64| {
+        let (final_after_tax, _, _) =
+            calculate_weekly_with_yearly_tax(principal, weekly_rate, weeks, weekly_contribution, capital_gains_tax);
+        final_after_tax
+    };
+    solver::find_root_increasing(|rate| balance_at(rate) - target_amount, MIN_SOLVED_RATE, 1.0, MAX_SOLVED_RATE, 1e-12)
 }
 
 /// Generate a year-by-year breakdown of compound interest
-pub fn generate_breakdown(params: &CompoundInterestParams) -> HashMap<u32, CompoundInterestResult> {
+pub fn generate_breakdown<T: Numeric>(params: &CompoundInterestParams<T>) -> HashMap<u32, CompoundInterestResult<T>> {
     let mut breakdown = HashMap::new();
     
-    for year in 1..=(params.years as u32) {
+    for year in 1..=(params.years.to_f64() as u32) {
         let year_params = CompoundInterestParams {
-            years: year as f64,
+            years: T::from_f64(year as f64),
             ..params.clone()
         };
         breakdown.insert(year, calculate_compound_interest(&year_params));
@@ -138,6 +867,38 @@ pub fn generate_breakdown(params: &CompoundInterestParams) -> HashMap<u32, Compo
     breakdown
 }
 
+/// Generate a year-by-year breakdown of compound interest with regular
+/// contributions, including the amount contributed in each year
+pub fn generate_contribution_breakdown<T: Numeric>(
+    params: &CompoundInterestParams<T>,
+    contribution: &Contribution<T>,
+) -> Vec<ContributionBreakdownRow<T>> {
+    let periods_per_year = T::from_f64(contribution.frequency.periods_per_year() as f64);
+    let mut rows = Vec::new();
+    let mut total_contributions = T::zero();
+    
+    for year in 1..=(params.years.to_f64() as u32) {
+        let year_params = CompoundInterestParams {
+            years: T::from_f64(year as f64),
+            ..params.clone()
+        };
+        let result = calculate_compound_interest_with_contributions(&year_params, contribution);
+        let contributions = contribution.escalation.amount_in_year(contribution.amount, year - 1) * periods_per_year;
+        total_contributions = total_contributions + contributions;
+        rows.push(ContributionBreakdownRow {
+            year,
+            contributions,
+            total_contributions,
+            final_amount: result.final_amount,
+            total_interest: result.total_interest,
+            real_final_amount: result.real_final_amount,
+            real_total_interest: result.real_total_interest,
+        });
+    }
+    
+    rows
+}
+
 /// Format currency values for display
 pub fn format_currency(amount: f64) -> String {
     // Handle negative numbers
@@ -182,6 +943,27 @@ pub fn calculate_weekly_with_yearly_tax(
     weeks: u32,
     weekly_contribution: f64,
     capital_gains_tax: f64,
+) -> (f64, f64, f64) {
+    calculate_weekly_with_yearly_tax_escalating(
+        principal,
+        weekly_rate,
+        weeks,
+        weekly_contribution,
+        Escalation::None,
+        capital_gains_tax,
+    )
+}
+
+/// Calculate weekly compounding with yearly capital gains tax, where the weekly
+/// contribution grows each year according to `escalation`
+/// Returns (final_amount_after_tax, total_interest_before_tax, total_tax_paid)
+pub fn calculate_weekly_with_yearly_tax_escalating(
+    principal: f64,
+    weekly_rate: f64,
+    weeks: u32,
+    first_year_weekly_contribution: f64,
+    escalation: Escalation,
+    capital_gains_tax: f64,
 ) -> (f64, f64, f64) {
     let weeks_per_year = 52;
     let years = weeks / weeks_per_year;
@@ -192,14 +974,15 @@ pub fn calculate_weekly_with_yearly_tax(
     let mut total_contributions = 0.0;
     
     // Process complete years
-    for _year in 0..years {
+    for year in 0..years {
+        let weekly_contribution = escalation.amount_in_year(first_year_weekly_contribution, year);
         let year_start_principal = current_principal;
         let year_contributions = weekly_contribution * weeks_per_year as f64;
         total_contributions += year_contributions;
         
         // Calculate growth for the year
         let year_end_principal = year_start_principal * (1.0 + weekly_rate).powf(weeks_per_year as f64);
-        let year_end_contributions = if weekly_rate > 0.0 {
+        let year_end_contributions = if weekly_rate != 0.0 {
             weekly_contribution * ((1.0 + weekly_rate).powf(weeks_per_year as f64) - 1.0) / weekly_rate
         } else {
             year_contributions
@@ -217,11 +1000,12 @@ pub fn calculate_weekly_with_yearly_tax(
     
     // Process remaining weeks
     if remaining_weeks > 0 {
+        let weekly_contribution = escalation.amount_in_year(first_year_weekly_contribution, years);
         let remaining_contributions = weekly_contribution * remaining_weeks as f64;
         total_contributions += remaining_contributions;
         
         let final_principal = current_principal * (1.0 + weekly_rate).powf(remaining_weeks as f64);
-        let final_contributions = if weekly_rate > 0.0 {
+        let final_contributions = if weekly_rate != 0.0 {
             weekly_contribution * ((1.0 + weekly_rate).powf(remaining_weeks as f64) - 1.0) / weekly_rate
         } else {
             remaining_contributions
@@ -250,12 +1034,7 @@ mod tests {
 
     #[test]
     fn test_basic_compound_interest() {
-        let params = CompoundInterestParams {
-            principal: 1000.0,
-            annual_rate: 0.05,
-            compounds_per_year: 1,
-            years: 10.0,
-        };
+        let params: CompoundInterestParams = CompoundInterestParams::new(1000.0, 0.05, Compounding::Annually, 10.0);
         
         let result = calculate_compound_interest(&params);
         
@@ -266,12 +1045,7 @@ mod tests {
 
     #[test]
     fn test_monthly_compounding() {
-        let params = CompoundInterestParams {
-            principal: 1000.0,
-            annual_rate: 0.05,
-            compounds_per_year: 12,
-            years: 1.0,
-        };
+        let params = CompoundInterestParams::new(1000.0, 0.05, Compounding::Monthly, 1.0);
         
         let result = calculate_compound_interest(&params);
         
@@ -281,37 +1055,374 @@ mod tests {
 
     #[test]
     fn test_compound_interest_with_contributions() {
-        let params = CompoundInterestParams {
-            principal: 1000.0,
-            annual_rate: 0.05,
-            compounds_per_year: 12,
-            years: 10.0,
-        };
+        let params = CompoundInterestParams::new(1000.0, 0.05, Compounding::Monthly, 10.0);
         
-        let result = calculate_compound_interest_with_contributions(&params, 100.0);
+        let result = calculate_compound_interest_with_contributions(&params, &Contribution::new(100.0));
         
         // Should be higher than without contributions
         let result_no_contributions = calculate_compound_interest(&params);
         assert!(result.final_amount > result_no_contributions.final_amount);
     }
 
+    #[test]
+    fn test_annuity_due_contributions() {
+        let params: CompoundInterestParams = CompoundInterestParams::new(0.0, 0.12, Compounding::Monthly, 1.0);
+        let ordinary = Contribution::new(100.0);
+        let due = Contribution { timing: ContributionTiming::Beginning, ..ordinary.clone() };
+        
+        let end = calculate_compound_interest_with_contributions(&params, &ordinary);
+        let beginning = calculate_compound_interest_with_contributions(&params, &due);
+        
+        // 100 * ((1.01^12 - 1) / 0.01) = 1268.25, and one extra month of growth when due
+        assert!((end.final_amount - 1268.25).abs() < 0.01);
+        assert!((beginning.final_amount - end.final_amount * 1.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contribution_frequency_matches_compounding() {
+        let params: CompoundInterestParams = CompoundInterestParams::new(1000.0, 0.06, Compounding::Annually, 5.0);
+        let annual = Contribution { frequency: PaymentFrequency::Annually, ..Contribution::new(1200.0) };
+        let weekly = Contribution { frequency: PaymentFrequency::Weekly, ..Contribution::new(1200.0 / 52.0) };
+        
+        // Annual deposits with annual compounding: 1200 * ((1.06^5 - 1) / 0.06) = 6764.51
+        let annual_result = calculate_compound_interest_with_contributions(&params, &annual);
+        assert!((annual_result.final_amount - (1338.23 + 6764.51)).abs() < 0.01);
+        
+        // Spreading the same yearly total across weeks earns more, at the same effective rate
+        let weekly_result = calculate_compound_interest_with_contributions(&params, &weekly);
+        assert!(weekly_result.final_amount > annual_result.final_amount);
+        let weekly_rate: f64 = Compounding::Annually.equivalent_periodic_rate(0.06, 52.0);
+        assert!(((1.0 + weekly_rate).powf(52.0) - 1.06).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_escalating_contributions() {
+        let params: CompoundInterestParams = CompoundInterestParams::new(0.0, 0.0, Compounding::Annually, 3.0);
+        let growing = Contribution {
+            escalation: Escalation::Percent(0.10),
+            ..Contribution::new(100.0)
+        };
+        
+        // With no interest the balance is just 12 * (100 + 110 + 121)
+        let result = calculate_compound_interest_with_contributions(&params, &growing);
+        assert!((result.final_amount - 3972.0).abs() < 1e-9);
+        
+        let rows = generate_contribution_breakdown(&params, &growing);
+        let yearly: Vec<f64> = rows.iter().map(|row| row.contributions).collect();
+        assert_eq!(rows.len(), 3);
+        assert!((yearly[2] - 1452.0).abs() < 1e-9);
+        assert!((rows[2].total_contributions - rows[2].final_amount).abs() < 1e-9);
+        
+        let stepped: Escalation = Escalation::Step(25.0);
+        assert!((stepped.amount_in_year(100.0, 2) - 150.0).abs() < 1e-12);
+    }
+
     #[test]
     fn test_time_to_target() {
-        let years = calculate_time_to_target(1000.0, 2000.0, 0.05, 1);
+        let years = calculate_time_to_target(1000.0, 2000.0, 0.05, Compounding::Annually);
         
         // Should take approximately 14.2 years to double at 5% annual interest
         assert!((years - 14.2).abs() < 0.5);
     }
 
+    #[test]
+    fn test_time_to_target_with_contributions() {
+        let contribution = Contribution::new(100.0);
+        
+        // Without interest, 12,000 of deposits takes exactly 10 years
+        let years = calculate_time_to_target_with_contributions(0.0, 12000.0, 0.0, Compounding::Monthly, &contribution, None).unwrap();
+        assert!((years - 10.0).abs() < 1e-6);
+        
+        // Tax drag slows growth down
+        let untaxed = calculate_time_to_target_with_contributions(1000.0, 50000.0, 0.07, Compounding::Monthly, &contribution, None).unwrap();
+        let taxed = calculate_time_to_target_with_contributions(1000.0, 50000.0, 0.07, Compounding::Monthly, &contribution, Some(0.3)).unwrap();
+        assert!(taxed > untaxed);
+        
+        let none = Contribution::new(0.0);
+        let result = calculate_time_to_target_with_contributions(1000.0, 2000.0, 0.0, Compounding::Monthly, &none, None);
+        assert_eq!(result, Err(CalcError::Unreachable));
+    }
+
+    #[test]
+    fn test_weeks_to_target_with_yearly_tax() {
+        let weeks = calculate_weeks_to_target_with_yearly_tax(10000.0, 20000.0, 0.01, 100.0, 0.3).unwrap();
+        let (before, _, _) = calculate_weekly_with_yearly_tax(10000.0, 0.01, weeks - 1, 100.0, 0.3);
+        let (after, _, _) = calculate_weekly_with_yearly_tax(10000.0, 0.01, weeks, 100.0, 0.3);
+        assert!(before < 20000.0 && after >= 20000.0);
+    }
+
     #[test]
     fn test_principal_for_target() {
-        let principal = calculate_principal_for_target(2000.0, 0.05, 1, 10.0);
+        let principal: f64 = calculate_principal_for_target(2000.0, 0.05, Compounding::Annually, 10.0);
         
         // Should be approximately 1227.83
         assert!((principal - 1227.83).abs() < 1.0);
     }
 
     #[test]
+    fn test_continuous_compounding() {
+        let params: CompoundInterestParams = CompoundInterestParams::new(1000.0, 0.05, Compounding::Continuous, 10.0);
+        
+        let result = calculate_compound_interest(&params);
+        
+        // A = 1000 * e^(0.05 * 10) = 1648.72
+        assert!((result.final_amount - 1648.72).abs() < 0.01);
+        assert!((result.effective_annual_rate - (0.05f64.exp() - 1.0)).abs() < 1e-12);
+        
+        // Zero periods per year must not divide by zero
+        assert_eq!(Compounding::from(0), Compounding::Continuous);
+        let per_year_zero = Compounding::PerYear(0).growth_factor(0.05, 10.0);
+        assert!((per_year_zero * 1000.0 - result.final_amount).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_continuous_target_functions() {
+        // ln(2) / 0.05 = 13.86 years to double
+        let years = calculate_time_to_target(1000.0, 2000.0, 0.05, Compounding::Continuous);
+        assert!((years - 2f64.ln() / 0.05).abs() < 1e-9);
+        
+        // 2000 * e^(-0.5) = 1213.06
+        let principal: f64 = calculate_principal_for_target(2000.0, 0.05, Compounding::Continuous, 10.0);
+        assert!((principal - 1213.06).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_accrual_between_dates() {
+        let start = Date::from_ymd(2024, 1, 1).unwrap();
+        let end = Date::from_ymd(2025, 1, 1).unwrap();
+        
+        // 366 days in 2024: a full year under ACT/ACT, slightly more under ACT/365 and ACT/360
+        let act_act = CompoundInterestParams::between_dates(1000.0, 0.05, Compounding::Daily, start, end, DayCount::ActAct);
+        let act_360 = CompoundInterestParams::between_dates(1000.0, 0.05, Compounding::Daily, start, end, DayCount::Act360);
+        assert!((act_act.years - 1.0).abs() < 1e-12);
+        assert!((act_360.years - 366.0 / 360.0).abs() < 1e-12);
+        assert!(calculate_compound_interest(&act_360).final_amount > calculate_compound_interest(&act_act).final_amount);
+    }
+
+    #[test]
+    fn test_contribution_for_target() {
+        let params: CompoundInterestParams = CompoundInterestParams::new(0.0, 0.0, Compounding::Monthly, 10.0);
+        let amount = calculate_contribution_for_target(&params, 12000.0, PaymentFrequency::Monthly, ContributionTiming::End).unwrap();
+        assert!((amount - 100.0).abs() < 1e-9);
+        
+        // Solving and projecting again lands on the target
+        let params = CompoundInterestParams {
+            principal: 5000.0,
+            annual_rate: 0.06,
+            ..params
+        };
+        let amount = calculate_contribution_for_target(&params, 100000.0, PaymentFrequency::Biweekly, ContributionTiming::Beginning).unwrap();
+        let contribution = Contribution {
+            frequency: PaymentFrequency::Biweekly,
+            timing: ContributionTiming::Beginning,
+            ..Contribution::new(amount)
+        };
+        let result = calculate_compound_interest_with_contributions(&params, &contribution);
+        assert!((result.final_amount - 100000.0).abs() < 1e-6);
+        
+        // The principal alone is already enough
+        let amount = calculate_contribution_for_target(&params, 5000.0, PaymentFrequency::Monthly, ContributionTiming::End).unwrap();
+        assert_eq!(amount, 0.0);
+    }
+
+    #[test]
+    fn test_rate_for_target() {
+        let none = Contribution::new(0.0);
+        let rate = calculate_rate_for_target(1000.0, 2000.0, Compounding::Annually, 10.0, &none).unwrap();
+        assert!((rate - (2.0f64.powf(0.1) - 1.0)).abs() < 1e-10);
+        
+        // Round trip with contributions
+        let contribution = Contribution::new(200.0);
+        let params = CompoundInterestParams::new(5000.0, 0.065, Compounding::Daily, 15.0);
+        let target = calculate_compound_interest_with_contributions(&params, &contribution).final_amount;
+        let rate = calculate_rate_for_target(5000.0, target, Compounding::Daily, 15.0, &contribution).unwrap();
+        assert!((rate - 0.065).abs() < 1e-9);
+        
+        // Losing money is a negative rate
+        let rate = calculate_rate_for_target(1000.0, 900.0, Compounding::Monthly, 1.0, &none).unwrap();
+        assert!(rate < 0.0);
+        
+        // Negative rates round trip with contributions too
+        let contribution = Contribution::new(100.0);
+        let rate = calculate_rate_for_target(1000.0, 2000.0, Compounding::Monthly, 1.0, &contribution).unwrap();
+        let params = CompoundInterestParams::new(1000.0, rate, Compounding::Monthly, 1.0);
+        assert!(rate < 0.0);
+        assert!((calculate_compound_interest_with_contributions(&params, &contribution).final_amount - 2000.0).abs() < 1e-6);
+        let rate = calculate_rate_for_target(0.0, 1100.0, Compounding::Monthly, 1.0, &contribution).unwrap();
+        let params = CompoundInterestParams { principal: 0.0, annual_rate: rate, ..params };
+        assert!(rate < 0.0);
+        assert!((calculate_compound_interest_with_contributions(&params, &contribution).final_amount - 1100.0).abs() < 1e-6);
+        
+        let result = calculate_rate_for_target(1000.0, 2000.0, Compounding::Monthly, 0.0, &none);
+        assert!(matches!(result, Err(CalcError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_weekly_rate_for_target() {
+        let (target, _, _) = calculate_weekly_with_yearly_tax(10000.0, 0.01, 130, 100.0, 0.25);
+        let rate = calculate_weekly_rate_for_target(10000.0, target, 130, 100.0, 0.25).unwrap();
+        assert!((rate - 0.01).abs() < 1e-9);
+        
+        // Paying in more than the target needs a negative rate
+        let rate = calculate_weekly_rate_for_target(0.0, 5000.0, 104, 100.0, 0.0).unwrap();
+        let (balance, _, _) = calculate_weekly_with_yearly_tax(0.0, rate, 104, 100.0, 0.0);
+        assert!(rate < 0.0);
+        assert!((balance - 5000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_inflation_adjusted_results() {
+        let params: CompoundInterestParams = CompoundInterestParams {
+            inflation_rate: 0.03,
+            ..CompoundInterestParams::new(1000.0, 0.05, Compounding::Annually, 10.0)
+        };
+        let result = calculate_compound_interest(&params);
+        assert!((result.real_final_amount - result.final_amount / 1.03f64.powi(10)).abs() < 1e-9);
+        assert!((result.real_total_interest - (result.real_final_amount - 1000.0)).abs() < 1e-9);
+        assert!((result.real_effective_annual_rate - (1.05 / 1.03 - 1.0)).abs() < 1e-12);
+        
+        // Without inflation the real results are the nominal ones
+        let nominal = calculate_compound_interest(&CompoundInterestParams { inflation_rate: 0.0, ..params.clone() });
+        assert!((nominal.real_final_amount - nominal.final_amount).abs() < 1e-9);
+        assert!((nominal.real_effective_annual_rate - nominal.effective_annual_rate).abs() < 1e-12);
+        
+        // Each breakdown row is deflated over its own years
+        let contribution = Contribution::new(100.0);
+        let result = calculate_compound_interest_with_contributions(&params, &contribution);
+        let rows = generate_contribution_breakdown(&params, &contribution);
+        let first = &rows[0];
+        assert!((first.real_final_amount - first.final_amount / 1.03).abs() < 1e-9);
+        assert!((rows[9].real_final_amount - result.real_final_amount).abs() < 1e-9);
+        assert!((rows[9].real_total_interest - result.real_total_interest).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_real_interest_deflates_each_contribution() {
+        // Earning exactly the inflation rate leaves no real gain
+        let params: CompoundInterestParams = CompoundInterestParams {
+            inflation_rate: 0.03,
+            ..CompoundInterestParams::new(1000.0, 0.03, Compounding::Annually, 10.0)
+        };
+        let contribution = Contribution::new(100.0);
+        let result = calculate_compound_interest_with_contributions(&params, &contribution);
+        assert!(result.real_total_interest.abs() < 1e-9);
+        
+        // 3% compounded monthly is slightly ahead of 3% inflation: deposits worth
+        // 10,376.24 today grow to 10,398.07 in today's money
+        let params = CompoundInterestParams {
+            principal: 0.0,
+            compounding: Compounding::Monthly,
+            ..params
+        };
+        let result = calculate_compound_interest_with_contributions(&params, &contribution);
+        assert!((result.real_final_amount - 10398.07).abs() < 0.01);
+        assert!((result.real_total_interest - (10398.07 - 10376.24)).abs() < 0.01);
+        assert!(result.real_effective_annual_rate > 0.0);
+    }
+
+    #[test]
+    fn test_rate_schedule() {
+        // 5% for two years, then 10%
+        let params: CompoundInterestParams = CompoundInterestParams {
+            principal: 1000.0,
+            annual_rate: 0.05,
+            compounding: Compounding::Annually,
+            years: 4.0,
+            inflation_rate: 0.0,
+            rate_schedule: vec![RateSegment { start_period: 2, annual_rate: 0.10 }],
+        };
+        assert_eq!(params.rate_in_period(1), 0.05);
+        assert_eq!(params.rate_in_period(2), 0.10);
+        let result = calculate_compound_interest(&params);
+        let expected = 1000.0 * 1.05f64.powi(2) * 1.1f64.powi(2);
+        assert!((result.final_amount - expected).abs() < 1e-9);
+        assert!((result.effective_annual_rate - ((1.05f64 * 1.1).sqrt() - 1.0)).abs() < 1e-12);
+        
+        // Segments count compounding periods, so a monthly schedule changes after 24 months
+        let monthly = CompoundInterestParams {
+            compounding: Compounding::Monthly,
+            rate_schedule: vec![RateSegment { start_period: 24, annual_rate: 0.10 }],
+            ..params.clone()
+        };
+        let expected = 1000.0 * (1.0 + 0.05 / 12.0f64).powi(24) * (1.0 + 0.10 / 12.0f64).powi(24);
+        assert!((calculate_compound_interest(&monthly).final_amount - expected).abs() < 1e-9);
+        
+        // Breakdown years before the change are unaffected by it
+        let breakdown = generate_breakdown(&params);
+        assert!((breakdown[&2].final_amount - 1000.0 * 1.05f64.powi(2)).abs() < 1e-9);
+        assert!((breakdown[&3].final_amount - 1000.0 * 1.05f64.powi(2) * 1.1).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_rate_schedule_with_contributions() {
+        // Nothing earned in the first year, 10% in the second
+        let params: CompoundInterestParams = CompoundInterestParams {
+            principal: 0.0,
+            annual_rate: 0.0,
+            compounding: Compounding::Annually,
+            years: 2.0,
+            inflation_rate: 0.0,
+            rate_schedule: vec![RateSegment { start_period: 1, annual_rate: 0.10 }],
+        };
+        let contribution = Contribution {
+            frequency: PaymentFrequency::Annually,
+            ..Contribution::new(100.0)
+        };
+        let result = calculate_compound_interest_with_contributions(&params, &contribution);
+        assert!((result.final_amount - 210.0).abs() < 1e-9);
+        assert!((result.total_interest - 10.0).abs() < 1e-9);
+        
+        // A schedule that never changes the rate matches the closed-form annuity
+        let params: CompoundInterestParams = CompoundInterestParams::new(5000.0, 0.06, Compounding::Quarterly, 7.5);
+        let contribution = Contribution {
+            timing: ContributionTiming::Beginning,
+            escalation: Escalation::Percent(0.03),
+            ..Contribution::new(200.0)
+        };
+        let constant = CompoundInterestParams {
+            rate_schedule: vec![RateSegment { start_period: 8, annual_rate: 0.06 }],
+            ..params.clone()
+        };
+        let closed_form = calculate_compound_interest_with_contributions(&params, &contribution);
+        let scheduled = calculate_compound_interest_with_contributions(&constant, &contribution);
+        assert!((scheduled.final_amount - closed_form.final_amount).abs() < 1e-6);
+        assert!((scheduled.total_interest - closed_form.total_interest).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_time_to_target_with_schedule() {
+        // Nothing for two years, then 10%: 1,210 is reached after four years
+        let params: CompoundInterestParams = CompoundInterestParams {
+            principal: 1000.0,
+            annual_rate: 0.0,
+            compounding: Compounding::Annually,
+            years: 0.0,
+            inflation_rate: 0.0,
+            rate_schedule: vec![RateSegment { start_period: 2, annual_rate: 0.10 }],
+        };
+        let none = Contribution::new(0.0);
+        let years = calculate_time_to_target_with_schedule(&params, 1210.0, &none, None).unwrap();
+        assert!((years - 4.0).abs() < 1e-6);
+        
+        // Halving each year's profit in tax leaves 5% a year after the change
+        let years = calculate_time_to_target_with_schedule(&params, 1000.0 * 1.05f64.powi(2), &none, Some(0.5)).unwrap();
+        assert!((years - 4.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_generic_over_f32() {
+        let params = CompoundInterestParams::new(1000.0f32, 0.05, Compounding::Monthly, 10.0);
+        
+        let result = calculate_compound_interest(&params);
+        let principal = calculate_principal_for_target(result.final_amount, 0.05f32, Compounding::Monthly, 10.0);
+        
+        // 1000 * (1 + 0.05/12)^120 = 1647.01, to within f32 precision
+        assert!((result.final_amount - 1647.01).abs() < 0.1);
+        assert!((principal - 1000.0).abs() < 0.1);
+    }
+
+    #[test]
+    #[allow(unused_variables)]
     fn test_weekly_with_tax() {
         let principal = 10000.0;
         let weekly_rate = 0.01; // 1% per week
@@ -343,4 +1454,20 @@ mod tests {
         let total_contributions = weekly_contribution * weeks as f64;
         assert!(final_after_tax > principal + total_contributions);
     }
+
+    #[test]
+    fn test_weekly_with_tax_escalating() {
+        let (final_level, _, _) = calculate_weekly_with_yearly_tax(10000.0, 0.01, 104, 100.0, 0.3);
+        
+        // A growing contribution ends higher than a level one
+        let (final_escalating, _, _) = calculate_weekly_with_yearly_tax_escalating(
+            10000.0,
+            0.01,
+            104,
+            100.0,
+            Escalation::Percent(0.05),
+            0.3,
+        );
+        assert!(final_escalating > final_level);
+    }
 }
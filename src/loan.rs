@@ -0,0 +1,551 @@
+use crate::date::Date;
+use crate::{Compounding, PaymentFrequency};
+
+/// Balance below which a loan counts as paid off
+const PAID_OFF: f64 = 1e-6;
+
+/// An extra payment towards principal on top of the regular payment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtraPayment {
+    /// A single extra payment made with the given payment number
+    OneOff { period: u32, amount: f64 },
+    /// An extra payment made with every `every`th payment, starting with payment `from_period`
+    Recurring { from_period: u32, every: u32, amount: f64 },
+}
+
+impl ExtraPayment {
+    /// Extra amount paid with the given payment number
+    pub fn amount_in_period(&self, period: u32) -> f64 {
+        match *self {
+            ExtraPayment::OneOff { period: due, amount } if due == period => amount,
+            ExtraPayment::Recurring {
+                from_period,
+                every,
+                amount,
+            } if period >= from_period && (period - from_period) % every.max(1) == 0 => amount,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Rate resets of an adjustable-rate loan
+///
+/// The loan's `annual_rate` applies for the first `fixed_periods` payments. At
+/// each reset the rate moves towards the index plus the margin, limited by the
+/// periodic caps, and always stays between the lifetime floor and cap.
+#[derive(Debug, Clone, Default)]
+pub struct RateAdjustment {
+    /// Number of payments at the initial rate before the first reset
+    pub fixed_periods: u32,
+    /// Number of payments between resets after the first
+    pub reset_every: u32,
+    /// Index rate at each reset in turn; the last one carries on for later resets
+    pub index_rates: Vec<f64>,
+    /// Margin added to the index rate
+    pub margin: f64,
+    /// Largest rise allowed at a single reset
+    pub periodic_cap: Option<f64>,
+    /// Largest fall allowed at a single reset
+    pub periodic_floor: Option<f64>,
+    /// Highest rate the loan can ever charge
+    pub lifetime_cap: Option<f64>,
+    /// Lowest rate the loan can ever charge
+    pub lifetime_floor: Option<f64>,
+}
+
+impl RateAdjustment {
+    /// Whether the rate resets with the given payment number
+    pub fn resets_at(&self, period: u32) -> bool {
+        period > self.fixed_periods && (period - self.fixed_periods - 1) % self.reset_every.max(1) == 0
+    }
+
+    /// Rate after the given reset, counting from 0, moving from `current_rate`
+    pub fn adjusted_rate(&self, reset: usize, current_rate: f64) -> f64 {
+        let index = match self.index_rates.get(reset).or(self.index_rates.last()) {
+            Some(index) => *index,
+            None => return current_rate,
+        };
+
+        let mut rate = index + self.margin;
+        if let Some(cap) = self.periodic_cap {
+            rate = rate.min(current_rate + cap);
+        }
+        if let Some(floor) = self.periodic_floor {
+            rate = rate.max(current_rate - floor);
+        }
+        if let Some(cap) = self.lifetime_cap {
+            rate = rate.min(cap);
+        }
+        if let Some(floor) = self.lifetime_floor {
+            rate = rate.max(floor);
+        }
+        rate
+    }
+}
+
+/// A loan repaid in regular instalments
+///
+/// By default the loan is fully amortizing at a fixed rate. It can also start
+/// with interest-only payments, amortize over a longer schedule than its term
+/// and end with a balloon payment, have an adjustable rate, and cap how far the
+/// payment can rise at a rate reset.
+#[derive(Debug, Clone)]
+pub struct Loan {
+    /// Amount borrowed
+    pub principal: f64,
+    /// Annual interest rate (as a decimal)
+    pub annual_rate: f64,
+    /// How often interest is compounded
+    pub compounding: Compounding,
+    /// Term of the loan in years
+    pub years: f64,
+    /// How often payments are made
+    pub frequency: PaymentFrequency,
+    /// Date the loan is taken out; the first payment is due one period later
+    pub start: Date,
+    /// Extra payments towards principal
+    pub extra_payments: Vec<ExtraPayment>,
+    /// Rate resets, for an adjustable-rate loan
+    pub rate_adjustment: Option<RateAdjustment>,
+    /// Number of payments at the start that only cover interest
+    pub interest_only_periods: u32,
+    /// Years the payment is calculated to amortize over, if longer than the
+    /// term; whatever is left at the end of the term is due as a balloon payment
+    pub amortization_years: Option<f64>,
+    /// Largest rise in the payment at a rate reset, as a fraction of the
+    /// previous payment; interest the capped payment does not cover is added
+    /// to the balance
+    pub payment_cap: Option<f64>,
+}
+
+impl Loan {
+    /// A loan repaid monthly with monthly compounding and no extra payments
+    pub fn new(principal: f64, annual_rate: f64, years: f64, start: Date) -> Self {
+        Loan {
+            principal,
+            annual_rate,
+            compounding: Compounding::Monthly,
+            years,
+            frequency: PaymentFrequency::Monthly,
+            start,
+            extra_payments: Vec::new(),
+            rate_adjustment: None,
+            interest_only_periods: 0,
+            amortization_years: None,
+            payment_cap: None,
+        }
+    }
+
+    /// Number of regular payments over the term
+    pub fn number_of_payments(&self) -> u32 {
+        (self.years * self.frequency.periods_per_year() as f64).round() as u32
+    }
+
+    /// Interest rate per payment period at the initial rate, equivalent to the compounding frequency
+    pub fn periodic_rate(&self) -> f64 {
+        self.periodic_rate_for(self.annual_rate)
+    }
+
+    /// Number of payments the amortizing payment is calculated over, including
+    /// any interest-only payments
+    pub fn amortization_periods(&self) -> u32 {
+        match self.amortization_years {
+            Some(years) => (years * self.frequency.periods_per_year() as f64).round() as u32,
+            None => self.number_of_payments(),
+        }
+    }
+
+    /// Regular amortizing payment at the initial rate, due once any
+    /// interest-only payments are over
+    pub fn payment(&self) -> f64 {
+        let periods = self.amortization_periods().saturating_sub(self.interest_only_periods);
+        level_payment(self.principal, self.periodic_rate(), periods)
+    }
+
+    fn periodic_rate_for(&self, annual_rate: f64) -> f64 {
+        let periods_per_year = self.frequency.periods_per_year() as f64;
+        self.compounding.equivalent_periodic_rate(annual_rate, periods_per_year)
+    }
+
+    /// Balance left after the last payment due on or before `date`
+    pub fn balance_on(&self, date: Date) -> f64 {
+        amortization_schedule(self)
+            .rows
+            .iter()
+            .take_while(|row| row.date <= date)
+            .last()
+            .map_or(self.principal, |row| row.balance)
+    }
+
+    /// Date the given payment is due, counting from 1
+    pub fn payment_date(&self, period: u32) -> Date {
+        let period = period as i64;
+        match self.frequency {
+            PaymentFrequency::Weekly => self.start.add_days(7 * period),
+            PaymentFrequency::Biweekly => self.start.add_days(14 * period),
+            PaymentFrequency::Semimonthly => self.start.add_months((period / 2) as i32).add_days(15 * (period % 2)),
+            PaymentFrequency::Monthly => self.start.add_months(period as i32),
+            PaymentFrequency::Quarterly => self.start.add_months(3 * period as i32),
+            PaymentFrequency::Annually => self.start.add_years(period as i32),
+        }
+    }
+}
+
+/// Payment that repays `balance` over `periods` equal instalments
+fn level_payment(balance: f64, periodic_rate: f64, periods: u32) -> f64 {
+    let periods = periods.max(1) as f64;
+    if periodic_rate == 0.0 {
+        balance / periods
+    } else {
+        balance * periodic_rate / (1.0 - (1.0 + periodic_rate).powf(-periods))
+    }
+}
+
+/// One payment of an amortization schedule
+#[derive(Debug, Clone)]
+pub struct AmortizationRow {
+    /// Payment number, starting at 1
+    pub period: u32,
+    /// Loan year the payment falls in, starting at 1
+    pub year: u32,
+    /// Date the payment is due
+    pub date: Date,
+    /// Annual interest rate charged for the period
+    pub rate: f64,
+    /// Regular payment, split into `interest` and `principal`
+    pub payment: f64,
+    /// Interest part of the payment
+    pub interest: f64,
+    /// Principal part of the payment
+    pub principal: f64,
+    /// Extra payment towards principal
+    pub extra: f64,
+    /// Balance left after the payment
+    pub balance: f64,
+    /// Whether the payment fell short of the interest, so the balance grew
+    pub negative_amortization: bool,
+}
+
+/// One year of an amortization schedule
+#[derive(Debug, Clone)]
+pub struct AmortizationYear {
+    /// Loan year, starting at 1
+    pub year: u32,
+    /// Total paid during the year, including extra payments
+    pub paid: f64,
+    /// Interest paid during the year
+    pub interest: f64,
+    /// Principal repaid during the year, including extra payments
+    pub principal: f64,
+    /// Balance left at the end of the year
+    pub balance: f64,
+}
+
+/// A loan's full amortization schedule and its totals
+#[derive(Debug, Clone)]
+pub struct AmortizationSchedule {
+    /// Every payment until the loan is repaid
+    pub rows: Vec<AmortizationRow>,
+    /// Regular payment per period
+    pub payment: f64,
+    /// Total paid, including extra payments
+    pub total_paid: f64,
+    /// Total interest paid
+    pub total_interest: f64,
+    /// Date of the final payment
+    pub payoff_date: Date,
+    /// Date of the final payment without the extra payments
+    pub scheduled_payoff_date: Date,
+    /// Interest saved by the extra payments
+    pub interest_saved: f64,
+    /// Number of regular payments the extra payments save
+    pub payments_saved: u32,
+    /// Balance left at the end of the term and paid with the final payment,
+    /// on top of the regular payment
+    pub balloon_payment: f64,
+}
+
+impl AmortizationSchedule {
+    /// Summarize the schedule one row per loan year
+    pub fn yearly(&self) -> Vec<AmortizationYear> {
+        let mut years: Vec<AmortizationYear> = Vec::new();
+        for row in &self.rows {
+            let paid = row.payment + row.extra;
+            let principal = row.principal + row.extra;
+            match years.last_mut() {
+                Some(last) if last.year == row.year => {
+                    last.paid += paid;
+                    last.interest += row.interest;
+                    last.principal += principal;
+                    last.balance = row.balance;
+                }
+                _ => years.push(AmortizationYear {
+                    year: row.year,
+                    paid,
+                    interest: row.interest,
+                    principal,
+                    balance: row.balance,
+                }),
+            }
+        }
+        years
+    }
+}
+
+/// Build the amortization schedule of a loan
+///
+/// Each payment first covers the interest accrued over its period and the rest
+/// repays principal. Extra payments go straight to principal, so the loan is
+/// repaid early; the schedule reports how much interest and time that saves
+/// compared with making the regular payments only. For an adjustable-rate loan
+/// the payment is recalculated at each reset to repay the remaining balance
+/// over the remaining term.
+///
+/// Interest-only payments leave the balance unchanged. If the payment does not
+/// cover the interest, as can happen when the payment is capped, the shortfall
+/// is added to the balance and the row is flagged as negative amortization.
+/// Whatever is left at the end of the term is paid with the final payment.
+pub fn amortization_schedule(loan: &Loan) -> AmortizationSchedule {
+    let (rows, balloon_payment) = amortize(loan, &loan.extra_payments);
+    let (scheduled, _) = amortize(loan, &[]);
+
+    let total_interest: f64 = rows.iter().map(|row| row.interest).sum();
+    let scheduled_interest: f64 = scheduled.iter().map(|row| row.interest).sum();
+    let last_date = |rows: &[AmortizationRow]| rows.last().map_or(loan.start, |row| row.date);
+
+    AmortizationSchedule {
+        payment: loan.payment(),
+        total_paid: rows.iter().map(|row| row.payment + row.extra).sum(),
+        total_interest,
+        payoff_date: last_date(&rows),
+        scheduled_payoff_date: last_date(&scheduled),
+        interest_saved: scheduled_interest - total_interest,
+        payments_saved: scheduled.len().saturating_sub(rows.len()) as u32,
+        balloon_payment,
+        rows,
+    }
+}
+
+/// Amortize a loan period by period with the given extra payments, returning
+/// the rows and the balloon paid with the final payment
+fn amortize(loan: &Loan, extra_payments: &[ExtraPayment]) -> (Vec<AmortizationRow>, f64) {
+    let total_periods = loan.number_of_payments();
+    let amortization_periods = loan.amortization_periods();
+    let interest_only_periods = loan.interest_only_periods;
+    let periods_per_year = loan.frequency.periods_per_year();
+    let mut annual_rate = loan.annual_rate;
+    let mut rate = loan.periodic_rate();
+    let mut payment = loan.payment();
+    let mut resets = 0;
+    let mut balloon_payment = 0.0;
+
+    let mut rows = Vec::new();
+    let mut balance = loan.principal;
+    for period in 1..=total_periods {
+        if balance <= PAID_OFF {
+            break;
+        }
+
+        let mut reset = false;
+        if let Some(adjustment) = loan.rate_adjustment.as_ref().filter(|adjustment| adjustment.resets_at(period)) {
+            annual_rate = adjustment.adjusted_rate(resets, annual_rate);
+            resets += 1;
+            rate = loan.periodic_rate_for(annual_rate);
+            reset = true;
+        }
+        // Recalculate the payment when amortization starts and at each reset after that
+        if period > interest_only_periods && (reset || period == interest_only_periods + 1) {
+            let remaining = amortization_periods.saturating_sub(period - 1);
+            let full_payment = level_payment(balance, rate, remaining);
+            payment = match loan.payment_cap {
+                Some(cap) if reset && period > interest_only_periods + 1 => full_payment.min(payment * (1.0 + cap)),
+                _ => full_payment,
+            };
+        }
+
+        let interest = balance * rate;
+        let due = if period <= interest_only_periods { interest } else { payment };
+        let scheduled_principal = (due - interest).min(balance);
+        // The final payment clears whatever is left, whether rounding or a balloon
+        let principal = if period == total_periods { balance } else { scheduled_principal };
+        if period == total_periods && balance - scheduled_principal > PAID_OFF {
+            balloon_payment = balance - scheduled_principal;
+        }
+        let extra: f64 = extra_payments.iter().map(|extra| extra.amount_in_period(period)).sum();
+        let extra = extra.min(balance - principal).max(0.0);
+        balance -= principal + extra;
+
+        rows.push(AmortizationRow {
+            period,
+            year: (period - 1) / periods_per_year + 1,
+            date: loan.payment_date(period),
+            rate: annual_rate,
+            payment: interest + principal,
+            interest,
+            principal,
+            extra,
+            balance,
+            negative_amortization: principal < 0.0,
+        });
+    }
+
+    (rows, balloon_payment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> Date {
+        Date::from_ymd(2024, 1, 15).unwrap()
+    }
+
+    #[test]
+    fn test_fixed_rate_schedule() {
+        let loan = Loan::new(200000.0, 0.06, 30.0, start());
+        let schedule = amortization_schedule(&loan);
+
+        assert!((schedule.payment - 1199.10).abs() < 0.005);
+        assert_eq!(schedule.rows.len(), 360);
+        assert!((schedule.rows[0].interest - 1000.0).abs() < 1e-9);
+        assert!(schedule.rows[359].balance.abs() < 1e-9);
+        assert!((schedule.total_interest - 231676.38).abs() < 0.5);
+        assert_eq!(schedule.payoff_date, Date::from_ymd(2054, 1, 15).unwrap());
+        assert_eq!(schedule.interest_saved, 0.0);
+        assert_eq!(schedule.yearly().len(), 30);
+    }
+
+    #[test]
+    fn test_extra_payments_save_interest() {
+        let mut loan = Loan::new(200000.0, 0.06, 30.0, start());
+        loan.extra_payments = vec![
+            ExtraPayment::Recurring {
+                from_period: 1,
+                every: 1,
+                amount: 200.0,
+            },
+            ExtraPayment::OneOff {
+                period: 12,
+                amount: 10000.0,
+            },
+        ];
+        let schedule = amortization_schedule(&loan);
+
+        assert_eq!(schedule.rows[11].extra, 10200.0);
+        assert!(schedule.rows.len() < 360);
+        assert!(schedule.payoff_date < schedule.scheduled_payoff_date);
+        assert!(schedule.interest_saved > 0.0);
+        assert_eq!(schedule.payments_saved, 360 - schedule.rows.len() as u32);
+
+        let repaid: f64 = schedule.rows.iter().map(|row| row.principal + row.extra).sum();
+        assert!((repaid - 200000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_adjustable_rate_resets() {
+        let loan = Loan {
+            rate_adjustment: Some(RateAdjustment {
+                fixed_periods: 60,
+                reset_every: 12,
+                index_rates: vec![0.05, 0.065, 0.01, 0.08],
+                margin: 0.0225,
+                periodic_cap: Some(0.02),
+                periodic_floor: Some(0.02),
+                lifetime_cap: Some(0.09),
+                lifetime_floor: Some(0.0225),
+            }),
+            ..Loan::new(200000.0, 0.04, 30.0, start())
+        };
+        let schedule = amortization_schedule(&loan);
+        let rate_at = |period: usize| schedule.rows[period - 1].rate;
+
+        assert_eq!(rate_at(60), 0.04);
+        // 7.25% indexed, limited to a 2% rise
+        assert!((rate_at(61) - 0.06).abs() < 1e-12);
+        assert!((rate_at(72) - 0.06).abs() < 1e-12);
+        // 8.75% indexed, limited to a 2% rise again
+        assert!((rate_at(73) - 0.08).abs() < 1e-12);
+        // 3.25% indexed, limited to a 2% fall
+        assert!((rate_at(85) - 0.06).abs() < 1e-12);
+        // 10.25% indexed, limited by the lifetime cap
+        assert!((rate_at(97) - 0.08).abs() < 1e-12);
+        assert!((rate_at(109) - 0.09).abs() < 1e-12);
+
+        // The payment is recalculated at each reset and still repays the loan on time
+        let balance_before_reset = schedule.rows[59].balance;
+        let expected = level_payment(balance_before_reset, 0.06 / 12.0, 300);
+        assert!((schedule.rows[60].payment - expected).abs() < 1e-9);
+        assert!(schedule.rows[60].payment > schedule.payment);
+        assert_eq!(schedule.rows.len(), 360);
+        assert!(schedule.rows[359].balance.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interest_only_then_amortizing() {
+        let loan = Loan {
+            interest_only_periods: 24,
+            ..Loan::new(100000.0, 0.06, 10.0, start())
+        };
+        let schedule = amortization_schedule(&loan);
+
+        assert!(schedule.rows[..24].iter().all(|row| row.principal == 0.0 && row.balance == 100000.0));
+        assert!((schedule.rows[0].payment - 500.0).abs() < 1e-9);
+        assert!((schedule.rows[24].payment - level_payment(100000.0, 0.005, 96)).abs() < 1e-9);
+        assert_eq!(schedule.rows.len(), 120);
+        assert_eq!(schedule.balloon_payment, 0.0);
+    }
+
+    #[test]
+    fn test_balloon_payment() {
+        // 30-year amortization due after 10 years
+        let loan = Loan {
+            amortization_years: Some(30.0),
+            ..Loan::new(1000000.0, 0.06, 10.0, start())
+        };
+        let schedule = amortization_schedule(&loan);
+
+        assert!((schedule.payment - 5995.51).abs() < 0.005);
+        assert_eq!(schedule.rows.len(), 120);
+        let last = &schedule.rows[119];
+        assert!((last.payment - schedule.payment - schedule.balloon_payment).abs() < 1e-6);
+        assert!((schedule.balloon_payment - 836857.25).abs() < 1.0);
+        assert_eq!(last.balance, 0.0);
+    }
+
+    #[test]
+    fn test_payment_cap_negative_amortization() {
+        let loan = Loan {
+            rate_adjustment: Some(RateAdjustment {
+                fixed_periods: 12,
+                reset_every: 12,
+                index_rates: vec![0.10],
+                ..RateAdjustment::default()
+            }),
+            payment_cap: Some(0.075),
+            ..Loan::new(200000.0, 0.02, 30.0, start())
+        };
+        let schedule = amortization_schedule(&loan);
+
+        // The payment rises by 7.5% at the reset, not enough to cover 10% interest
+        let row = &schedule.rows[12];
+        assert!((row.payment - schedule.rows[11].payment * 1.075).abs() < 1e-9);
+        assert!(row.negative_amortization);
+        assert!(row.balance > schedule.rows[11].balance);
+        assert!(!schedule.rows[11].negative_amortization);
+        assert_eq!(schedule.rows.last().unwrap().balance, 0.0);
+    }
+
+    #[test]
+    fn test_zero_rate_and_payment_dates() {
+        let loan = Loan {
+            frequency: PaymentFrequency::Semimonthly,
+            ..Loan::new(2400.0, 0.0, 1.0, start())
+        };
+        let schedule = amortization_schedule(&loan);
+
+        assert_eq!(schedule.payment, 100.0);
+        assert_eq!(schedule.rows[0].date, Date::from_ymd(2024, 1, 30).unwrap());
+        assert_eq!(schedule.rows[1].date, Date::from_ymd(2024, 2, 15).unwrap());
+        assert_eq!(schedule.total_interest, 0.0);
+    }
+}
@@ -7,13 +7,22 @@ fn main() {
     loop {
         println!("Choose an option:");
         println!("1. Calculate compound interest");
-        println!("2. Calculate compound interest with monthly contributions");
+        println!("2. Calculate compound interest with regular contributions");
         println!("3. Calculate time to reach target amount");
         println!("4. Calculate required principal for target amount");
         println!("5. Generate year-by-year breakdown");
         println!("6. Exit");
         println!("7. Calculate weekly compounding with yearly tax (trader scenario)");
-        print!("\nEnter your choice (1-7): ");
+        println!("8. Plan withdrawals from a balance");
+        println!("9. Calculate required contribution for target amount");
+        println!("10. Analyze dated cash flows from a file (NPV, XIRR)");
+        println!("11. Build a loan amortization schedule");
+        println!("12. Compare refinancing a loan");
+        println!("13. Simulate random returns (Monte Carlo)");
+        println!("14. Backtest a plan on historical returns from a file");
+        println!("15. Resample historical returns from a file (bootstrap)");
+        println!("16. Analyze sequence-of-returns risk for a withdrawal plan");
+        print!("\nEnter your choice (1-16): ");
         io::stdout().flush().unwrap();
         
         let mut choice = String::new();
@@ -31,6 +40,15 @@ fn main() {
                 break;
             }
             "7" => calculate_weekly_with_tax_interactive(),
+            "8" => plan_withdrawals_interactive(),
+            "9" => calculate_contribution_for_target_interactive(),
+            "10" => analyze_cash_flows_interactive(),
+            "11" => amortize_loan_interactive(),
+            "12" => compare_refinance_interactive(),
+            "13" => simulate_returns_interactive(),
+            "14" => backtest_returns_interactive(),
+            "15" => bootstrap_returns_interactive(),
+            "16" => sequence_risk_interactive(),
             _ => println!("Invalid choice. Please try again.\n"),
         }
     }
@@ -66,19 +84,96 @@ fn get_u32_input(prompt: &str) -> u32 {
     }
 }
 
+fn get_string_input(prompt: &str) -> String {
+    print!("{}: ", prompt);
+    io::stdout().flush().unwrap();
+    
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim().to_string()
+}
+
+fn get_date_input(prompt: &str) -> Date {
+    loop {
+        match get_string_input(prompt).parse::<Date>() {
+            Ok(date) => return date,
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+fn get_frequency_input(prompt: &str) -> PaymentFrequency {
+    loop {
+        match PaymentFrequency::from_periods_per_year(get_u32_input(prompt)) {
+            Some(frequency) => return frequency,
+            None => println!("Please enter one of 52, 26, 24, 12, 4 or 1."),
+        }
+    }
+}
+
+fn get_timing_input(prompt: &str) -> ContributionTiming {
+    loop {
+        print!("{}: ", prompt);
+        io::stdout().flush().unwrap();
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        
+        match input.trim().to_lowercase().as_str() {
+            "b" | "beginning" => return ContributionTiming::Beginning,
+            "e" | "end" | "" => return ContributionTiming::End,
+            _ => println!("Please enter 'b' for beginning or 'e' for end."),
+        }
+    }
+}
+
+fn get_compounding_input() -> Compounding {
+    Compounding::from(get_u32_input("Enter number of times interest is compounded per year (1=annually, 12=monthly, 365=daily, 0=continuous)"))
+}
+
+fn get_rate_schedule_input() -> Vec<RateSegment> {
+    'input: loop {
+        let input = get_string_input("Enter later rate changes as period:rate pairs (compounding periods, e.g., 24:0.04, 60:0.03; blank for none)");
+        let mut schedule = Vec::new();
+        for pair in input.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let parsed = pair
+                .split_once(':')
+                .and_then(|(period, rate)| Some((period.trim().parse().ok()?, rate.trim().parse().ok()?)));
+            match parsed {
+                Some((start_period, annual_rate)) => schedule.push(RateSegment { start_period, annual_rate }),
+                None => {
+                    println!("Please enter pairs like 24:0.04, separated by commas.");
+                    continue 'input;
+                }
+            }
+        }
+        return schedule;
+    }
+}
+
+fn print_rate_schedule(schedule: &[RateSegment]) {
+    for segment in schedule {
+        println!("  From period {}: {}", segment.start_period, format_percentage(segment.annual_rate));
+    }
+}
+
 fn calculate_basic_interest() {
     println!("\n--- Basic Compound Interest Calculation ---\n");
     
     let principal = get_float_input("Enter principal amount ($)");
     let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
-    let compounds_per_year = get_u32_input("Enter number of times interest is compounded per year (1=annually, 12=monthly, 365=daily)");
+    let compounding = get_compounding_input();
+    let rate_schedule = get_rate_schedule_input();
     let years = get_float_input("Enter number of years");
+    let inflation_rate = get_float_input("Enter expected annual inflation rate (as decimal, e.g., 0.03 for 3%, 0 for none)");
     
     let params = CompoundInterestParams {
         principal,
         annual_rate,
-        compounds_per_year,
+        compounding,
         years,
+        inflation_rate,
+        rate_schedule,
     };
     
     let result = calculate_compound_interest(&params);
@@ -86,52 +181,105 @@ fn calculate_basic_interest() {
     println!("\n=== Results ===");
     println!("Initial Principal: {}", format_currency(result.principal));
     println!("Annual Interest Rate: {}", format_percentage(params.annual_rate));
-    println!("Compounding Frequency: {} times per year", params.compounds_per_year);
+    print_rate_schedule(&params.rate_schedule);
+    println!("Compounding Frequency: {}", params.compounding);
     println!("Time Period: {:.1} years", params.years);
     println!("Final Amount: {}", format_currency(result.final_amount));
     println!("Total Interest Earned: {}", format_currency(result.total_interest));
     println!("Effective Annual Rate: {}", format_percentage(result.effective_annual_rate));
     println!("Growth Factor: {:.2}x", result.final_amount / result.principal);
+    if params.inflation_rate != 0.0 {
+        println!();
+        println!("--- In Today's Money ({} inflation) ---", format_percentage(params.inflation_rate));
+        println!("Real Final Amount: {}", format_currency(result.real_final_amount));
+        println!("Real Interest Earned: {}", format_currency(result.real_total_interest));
+        println!("Real Effective Annual Rate: {}", format_percentage(result.real_effective_annual_rate));
+    }
     println!();
 }
 
 fn calculate_interest_with_contributions() {
-    println!("\n--- Compound Interest with Monthly Contributions ---\n");
+    println!("\n--- Compound Interest with Regular Contributions ---\n");
     
     let principal = get_float_input("Enter initial principal amount ($)");
     let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
-    let compounds_per_year = get_u32_input("Enter number of times interest is compounded per year (1=annually, 12=monthly, 365=daily)");
+    let compounding = get_compounding_input();
+    let rate_schedule = get_rate_schedule_input();
     let years = get_float_input("Enter number of years");
-    let monthly_contribution = get_float_input("Enter monthly contribution amount ($)");
+    let frequency = get_frequency_input("Enter contribution frequency (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let periodic_contribution = get_float_input("Enter contribution amount per period ($)");
+    let timing = get_timing_input("Are contributions made at the beginning or end of each period? (b/e)");
+    let escalation_rate = get_float_input("Enter yearly increase in contributions (as decimal, e.g., 0.03 for 3%, 0 for none)");
+    let inflation_rate = get_float_input("Enter expected annual inflation rate (as decimal, e.g., 0.03 for 3%, 0 for none)");
     
     let params = CompoundInterestParams {
         principal,
         annual_rate,
-        compounds_per_year,
+        compounding,
         years,
+        inflation_rate,
+        rate_schedule,
     };
     
-    let result = calculate_compound_interest_with_contributions(&params, monthly_contribution);
+    let escalation = if escalation_rate != 0.0 {
+        Escalation::Percent(escalation_rate)
+    } else {
+        Escalation::None
+    };
+    let contribution = Contribution {
+        amount: periodic_contribution,
+        frequency,
+        timing,
+        escalation,
+    };
+    let result = calculate_compound_interest_with_contributions(&params, &contribution);
     let result_no_contributions = calculate_compound_interest(&params);
     
-    let total_contributions = monthly_contribution * years * 12.0;
+    let total_contributions = result.final_amount - result.principal - result.total_interest;
     
     println!("\n=== Results ===");
     println!("Initial Principal: {}", format_currency(result.principal));
-    println!("Monthly Contribution: {}", format_currency(monthly_contribution));
+    println!("Contribution: {} ({})", format_currency(periodic_contribution), frequency);
+    println!("Yearly Contribution Increase: {}", format_percentage(escalation_rate));
+    println!("Contribution Timing: {}", match timing {
+        ContributionTiming::Beginning => "beginning of each period",
+        ContributionTiming::End => "end of each period",
+    });
     println!("Total Contributions: {}", format_currency(total_contributions));
     println!("Annual Interest Rate: {}", format_percentage(params.annual_rate));
-    println!("Compounding Frequency: {} times per year", params.compounds_per_year);
+    print_rate_schedule(&params.rate_schedule);
+    println!("Compounding Frequency: {}", params.compounding);
     println!("Time Period: {:.1} years", params.years);
     println!("Final Amount: {}", format_currency(result.final_amount));
     println!("Total Interest Earned: {}", format_currency(result.total_interest));
     println!("Effective Annual Rate: {}", format_percentage(result.effective_annual_rate));
+    if params.inflation_rate != 0.0 {
+        println!();
+        println!("--- In Today's Money ({} inflation) ---", format_percentage(params.inflation_rate));
+        println!("Real Final Amount: {}", format_currency(result.real_final_amount));
+        println!("Real Interest Earned: {}", format_currency(result.real_total_interest));
+        println!("Real Effective Annual Rate: {}", format_percentage(result.real_effective_annual_rate));
+    }
     println!();
     println!("--- Comparison ---");
     println!("Without contributions: {}", format_currency(result_no_contributions.final_amount));
     println!("With contributions: {}", format_currency(result.final_amount));
     println!("Difference: {}", format_currency(result.final_amount - result_no_contributions.final_amount));
     println!();
+    println!("{:<6} {:<15} {:<15} {:<15} {:<15}", "Year", "Contributed", "Amount", "Interest", "Real Amount");
+    println!("{:-<76}", "");
+    
+    for row in generate_contribution_breakdown(&params, &contribution) {
+        println!(
+            "{:<6} {:<15} {:<15} {:<15} {:<15}",
+            row.year,
+            format_currency(row.contributions),
+            format_currency(row.final_amount),
+            format_currency(row.total_interest),
+            format_currency(row.real_final_amount)
+        );
+    }
+    println!();
 }
 
 fn calculate_time_to_target_interactive() {
@@ -140,23 +288,45 @@ fn calculate_time_to_target_interactive() {
     let principal = get_float_input("Enter current principal amount ($)");
     let target_amount = get_float_input("Enter target amount ($)");
     let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
-    let compounds_per_year = get_u32_input("Enter number of times interest is compounded per year (1=annually, 12=monthly, 365=daily)");
+    let compounding = get_compounding_input();
+    let rate_schedule = get_rate_schedule_input();
+    let frequency = get_frequency_input("Enter contribution frequency (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let periodic_contribution = get_float_input("Enter contribution amount per period ($, 0 for none)");
+    let tax_rate = get_float_input("Enter yearly capital gains tax rate (as decimal, e.g., 0.25 for 25%, 0 for none)");
     
-    let years = calculate_time_to_target(principal, target_amount, annual_rate, compounds_per_year);
+    let contribution = Contribution {
+        frequency,
+        ..Contribution::new(periodic_contribution)
+    };
+    let capital_gains_tax = if tax_rate != 0.0 { Some(tax_rate) } else { None };
+    let params = CompoundInterestParams {
+        principal,
+        annual_rate,
+        compounding,
+        years: 0.0,
+        inflation_rate: 0.0,
+        rate_schedule,
+    };
     
-    if years > 0.0 {
-        println!("\n=== Results ===");
-        println!("Current Principal: {}", format_currency(principal));
-        println!("Target Amount: {}", format_currency(target_amount));
-        println!("Annual Interest Rate: {}", format_percentage(annual_rate));
-        println!("Compounding Frequency: {} times per year", compounds_per_year);
-        println!("Time to reach target: {:.1} years", years);
-        println!("Time to reach target: {:.0} months", years * 12.0);
-        println!();
-    } else {
-        println!("\nError: Cannot reach target amount with given parameters.");
-        println!("Make sure your interest rate is positive and target amount is greater than principal.");
-        println!();
+    match calculate_time_to_target_with_schedule(&params, target_amount, &contribution, capital_gains_tax) {
+        Ok(years) => {
+            println!("\n=== Results ===");
+            println!("Current Principal: {}", format_currency(principal));
+            println!("Target Amount: {}", format_currency(target_amount));
+            println!("Contribution: {} ({})", format_currency(periodic_contribution), frequency);
+            println!("Annual Interest Rate: {}", format_percentage(annual_rate));
+            print_rate_schedule(&params.rate_schedule);
+            println!("Compounding Frequency: {}", compounding);
+            println!("Capital Gains Tax: {}", format_percentage(tax_rate));
+            println!("Time to reach target: {:.1} years", years);
+            println!("Time to reach target: {:.0} months", years * 12.0);
+            println!();
+        }
+        Err(error) => {
+            println!("\nError: {}.", error);
+            println!("Make sure your interest rate or contributions are positive.");
+            println!();
+        }
     }
 }
 
@@ -165,16 +335,16 @@ fn calculate_principal_for_target_interactive() {
     
     let target_amount = get_float_input("Enter target amount ($)");
     let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
-    let compounds_per_year = get_u32_input("Enter number of times interest is compounded per year (1=annually, 12=monthly, 365=daily)");
+    let compounding = get_compounding_input();
     let years = get_float_input("Enter number of years");
     
-    let principal = calculate_principal_for_target(target_amount, annual_rate, compounds_per_year, years);
+    let principal = calculate_principal_for_target(target_amount, annual_rate, compounding, years);
     
     if principal > 0.0 {
         println!("\n=== Results ===");
         println!("Target Amount: {}", format_currency(target_amount));
         println!("Annual Interest Rate: {}", format_percentage(annual_rate));
-        println!("Compounding Frequency: {} times per year", compounds_per_year);
+        println!("Compounding Frequency: {}", compounding);
         println!("Time Period: {:.1} years", years);
         println!("Required Principal: {}", format_currency(principal));
         println!();
@@ -185,19 +355,64 @@ fn calculate_principal_for_target_interactive() {
     }
 }
 
+fn calculate_contribution_for_target_interactive() {
+    println!("\n--- Required Contribution for Target Amount ---\n");
+    
+    let target_amount = get_float_input("Enter target amount ($)");
+    let principal = get_float_input("Enter starting principal amount ($, 0 for none)");
+    let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
+    let compounding = get_compounding_input();
+    let years = get_float_input("Enter number of years");
+    let frequency = get_frequency_input("Enter contribution frequency (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let timing = get_timing_input("Are contributions made at the beginning or end of each period? (b/e)");
+    
+    let params = CompoundInterestParams::new(principal, annual_rate, compounding, years);
+    
+    match calculate_contribution_for_target(&params, target_amount, frequency, timing) {
+        Ok(amount) => {
+            let contribution = Contribution {
+                frequency,
+                timing,
+                ..Contribution::new(amount)
+            };
+            let result = calculate_compound_interest_with_contributions(&params, &contribution);
+            let total_contributions = result.final_amount - result.principal - result.total_interest;
+            println!("\n=== Results ===");
+            println!("Target Amount: {}", format_currency(target_amount));
+            println!("Starting Principal: {}", format_currency(principal));
+            println!("Annual Interest Rate: {}", format_percentage(annual_rate));
+            println!("Compounding Frequency: {}", compounding);
+            println!("Time Period: {:.1} years", years);
+            println!("Required Contribution: {} ({})", format_currency(amount), frequency);
+            println!("Total Contributions: {}", format_currency(total_contributions));
+            println!("Final Amount: {}", format_currency(result.final_amount));
+            println!("Total Interest Earned: {}", format_currency(result.total_interest));
+            println!();
+        }
+        Err(error) => {
+            println!("\nError: {}.", error);
+            println!();
+        }
+    }
+}
+
 fn generate_breakdown_interactive() {
     println!("\n--- Year-by-Year Breakdown ---\n");
     
     let principal = get_float_input("Enter principal amount ($)");
     let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
-    let compounds_per_year = get_u32_input("Enter number of times interest is compounded per year (1=annually, 12=monthly, 365=daily)");
+    let compounding = get_compounding_input();
+    let rate_schedule = get_rate_schedule_input();
     let years = get_float_input("Enter number of years");
+    let inflation_rate = get_float_input("Enter expected annual inflation rate (as decimal, e.g., 0.03 for 3%, 0 for none)");
     
     let params = CompoundInterestParams {
         principal,
         annual_rate,
-        compounds_per_year,
+        compounding,
         years,
+        inflation_rate,
+        rate_schedule,
     };
     
     let breakdown = generate_breakdown(&params);
@@ -205,19 +420,21 @@ fn generate_breakdown_interactive() {
     println!("\n=== Year-by-Year Breakdown ===");
     println!("Initial Principal: {}", format_currency(principal));
     println!("Annual Interest Rate: {}", format_percentage(annual_rate));
-    println!("Compounding Frequency: {} times per year", compounds_per_year);
+    print_rate_schedule(&params.rate_schedule);
+    println!("Compounding Frequency: {}", compounding);
     println!();
-    println!("{:<6} {:<15} {:<15} {:<15}", "Year", "Amount", "Interest", "Growth");
-    println!("{:-<60}", "");
+    println!("{:<6} {:<15} {:<15} {:<15} {:<15}", "Year", "Amount", "Interest", "Growth", "Real Amount");
+    println!("{:-<76}", "");
     
     for year in 1..=(years as u32) {
         if let Some(result) = breakdown.get(&year) {
             println!(
-                "{:<6} {:<15} {:<15} {:<15}",
+                "{:<6} {:<15} {:<15} {:<15} {:<15}",
                 year,
                 format_currency(result.final_amount),
                 format_currency(result.total_interest),
-                format!("{:.2}x", result.final_amount / principal)
+                format!("{:.2}x", result.final_amount / principal),
+                format_currency(result.real_final_amount)
             );
         }
     }
@@ -230,16 +447,23 @@ fn calculate_weekly_with_tax_interactive() {
     let weekly_rate = get_float_input("Enter weekly rate of return (as decimal, e.g., 0.02 for 2%)");
     let weeks = get_u32_input("Enter number of weeks to extrapolate");
     let weekly_contribution = get_float_input("Enter weekly contribution amount ($)");
+    let escalation_rate = get_float_input("Enter yearly increase in weekly contribution (as decimal, e.g., 0.03 for 3%, 0 for none)");
     let capital_gains_tax = get_float_input("Enter capital gains tax rate (as decimal, e.g., 0.37 for 37%)");
 
-    let (final_after_tax, profit, tax_paid) = cical::calculate_weekly_with_yearly_tax(
+    let escalation = if escalation_rate != 0.0 {
+        Escalation::Percent(escalation_rate)
+    } else {
+        Escalation::None
+    };
+    let (final_after_tax, profit, tax_paid) = cical::calculate_weekly_with_yearly_tax_escalating(
         principal,
         weekly_rate,
         weeks,
         weekly_contribution,
+        escalation,
         capital_gains_tax,
     );
-    let total_contributions = weekly_contribution * weeks as f64;
+    let total_contributions = final_after_tax + tax_paid - principal - profit;
     let final_before_tax = final_after_tax + tax_paid;
     println!("\n=== Results ===");
     println!("Initial Principal: {}", cical::format_currency(principal));
@@ -255,4 +479,511 @@ fn calculate_weekly_with_tax_interactive() {
     println!("Final Amount (after tax): {}", cical::format_currency(final_after_tax));
     println!("Growth Factor (after tax): {:.2}x", final_after_tax / (principal + total_contributions));
     println!();
-} 
\ No newline at end of file
+} 
+
+fn plan_withdrawals_interactive() {
+    println!("\n--- Withdrawal Plan ---\n");
+    
+    let balance = get_float_input("Enter starting balance ($)");
+    let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
+    let compounding = get_compounding_input();
+    let frequency = get_frequency_input("Enter withdrawal frequency (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let withdrawal = get_float_input("Enter withdrawal amount per period ($)");
+    let timing = get_timing_input("Are withdrawals taken at the beginning or end of each period? (b/e)");
+    let inflation_rate = get_float_input("Enter yearly inflation increase in withdrawals (as decimal, e.g., 0.03 for 3%, 0 for fixed)");
+    let years = get_float_input("Enter maximum number of years to project");
+    
+    let plan = WithdrawalPlan {
+        balance,
+        annual_rate,
+        compounding,
+        withdrawal,
+        frequency,
+        timing,
+        indexation: if inflation_rate != 0.0 { Escalation::Percent(inflation_rate) } else { Escalation::None },
+        years,
+        inflation_rate,
+    };
+    let result = calculate_withdrawals(&plan);
+    
+    println!("\n=== Results ===");
+    println!("Starting Balance: {}", format_currency(balance));
+    println!("Annual Interest Rate: {}", format_percentage(annual_rate));
+    println!("Compounding Frequency: {}", compounding);
+    println!("Withdrawal: {} ({})", format_currency(withdrawal), frequency);
+    println!("Yearly Withdrawal Increase: {}", format_percentage(inflation_rate));
+    match result.years_lasted {
+        Some(years_lasted) => println!("Money lasts: {:.1} years", years_lasted),
+        None => println!("Money lasts: more than {:.1} years", years),
+    }
+    println!("Total Withdrawn: {}", format_currency(result.total_withdrawn));
+    println!("Total Interest Earned: {}", format_currency(result.total_interest));
+    println!("Final Balance: {}", format_currency(result.final_balance));
+    if inflation_rate != 0.0 {
+        println!();
+        println!("--- In Today's Money ({} inflation) ---", format_percentage(inflation_rate));
+        println!("Real Total Withdrawn: {}", format_currency(result.real_total_withdrawn));
+        println!("Real Final Balance: {}", format_currency(result.real_final_balance));
+    }
+    println!();
+    println!("{:<6} {:<15} {:<15} {:<15}", "Year", "Withdrawn", "Interest", "Balance");
+    println!("{:-<60}", "");
+    
+    for year in result.yearly() {
+        println!(
+            "{:<6} {:<15} {:<15} {:<15}",
+            year.year,
+            format_currency(year.withdrawn),
+            format_currency(year.interest),
+            format_currency(year.balance)
+        );
+    }
+    println!();
+}
+
+fn analyze_cash_flows_interactive() {
+    println!("\n--- Dated Cash Flow Analysis ---\n");
+    println!("The file should list one flow per line as YYYY-MM-DD,amount");
+    println!("(money paid in negative, money received or the current balance positive).\n");
+    
+    let path = get_string_input("Enter path to cash flow file");
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) => {
+            println!("\nError: could not read {}: {}\n", path, error);
+            return;
+        }
+    };
+    let flows = match parse_cash_flows(&text) {
+        Ok(flows) => flows,
+        Err(error) => {
+            println!("\nError: {}\n", error);
+            return;
+        }
+    };
+    let discount_rate = get_float_input("Enter annual discount rate for NPV (as decimal, e.g., 0.05 for 5%)");
+    
+    let paid_in: f64 = flows.iter().filter(|flow| flow.amount < 0.0).map(|flow| -flow.amount).sum();
+    let received: f64 = flows.iter().filter(|flow| flow.amount > 0.0).map(|flow| flow.amount).sum();
+    
+    println!("\n{:<12} {:<15}", "Date", "Amount");
+    println!("{:-<30}", "");
+    for flow in &flows {
+        println!("{:<12} {:<15}", flow.date.to_string(), format_currency(flow.amount));
+    }
+    
+    println!("\n=== Results ===");
+    println!("Cash Flows: {}", flows.len());
+    println!("Total Paid In: {}", format_currency(paid_in));
+    println!("Total Received: {}", format_currency(received));
+    println!("Net Gain: {}", format_currency(received - paid_in));
+    println!("NPV at {}: {}", format_percentage(discount_rate), format_currency(xnpv(discount_rate, &flows)));
+    match xirr(&flows) {
+        Ok(rate) => println!("Internal Rate of Return (XIRR): {}", format_percentage(rate)),
+        Err(error) => println!("Internal Rate of Return (XIRR): not available ({})", error),
+    }
+    println!();
+}
+
+fn amortize_loan_interactive() {
+    println!("\n--- Loan Amortization Schedule ---\n");
+    
+    let principal = get_float_input("Enter loan amount ($)");
+    let annual_rate = get_float_input("Enter annual interest rate (as decimal, e.g., 0.05 for 5%)");
+    let years = get_float_input("Enter loan term in years");
+    let frequency = get_frequency_input("Enter payment frequency (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let start = get_date_input("Enter loan start date (YYYY-MM-DD)");
+    let recurring_extra = get_float_input("Enter extra payment with every payment ($, 0 for none)");
+    let one_off_extra = get_float_input("Enter one-off extra payment ($, 0 for none)");
+    
+    let mut loan = Loan {
+        compounding: Compounding::from(frequency.periods_per_year()),
+        frequency,
+        ..Loan::new(principal, annual_rate, years, start)
+    };
+    if recurring_extra > 0.0 {
+        loan.extra_payments.push(ExtraPayment::Recurring {
+            from_period: 1,
+            every: 1,
+            amount: recurring_extra,
+        });
+    }
+    if one_off_extra > 0.0 {
+        let period = get_u32_input("Enter payment number the one-off extra payment is made with");
+        loan.extra_payments.push(ExtraPayment::OneOff {
+            period,
+            amount: one_off_extra,
+        });
+    }
+    let schedule = amortization_schedule(&loan);
+    
+    println!("\n=== Results ===");
+    println!("Loan Amount: {}", format_currency(principal));
+    println!("Annual Interest Rate: {}", format_percentage(annual_rate));
+    println!("Term: {:.1} years ({} payments)", years, loan.number_of_payments());
+    println!("Regular Payment: {} ({})", format_currency(schedule.payment), frequency);
+    println!("Total Paid: {}", format_currency(schedule.total_paid));
+    println!("Total Interest: {}", format_currency(schedule.total_interest));
+    println!("Payoff Date: {}", schedule.payoff_date);
+    if !loan.extra_payments.is_empty() {
+        println!("Payoff Date Without Extra Payments: {}", schedule.scheduled_payoff_date);
+        println!("Payments Saved: {}", schedule.payments_saved);
+        println!("Interest Saved: {}", format_currency(schedule.interest_saved));
+    }
+    println!();
+    println!("{:<6} {:<15} {:<15} {:<15} {:<15}", "Year", "Paid", "Interest", "Principal", "Balance");
+    println!("{:-<75}", "");
+    
+    for year in schedule.yearly() {
+        println!(
+            "{:<6} {:<15} {:<15} {:<15} {:<15}",
+            year.year,
+            format_currency(year.paid),
+            format_currency(year.interest),
+            format_currency(year.principal),
+            format_currency(year.balance)
+        );
+    }
+    println!();
+}
+
+fn compare_refinance_interactive() {
+    println!("\n--- Refinance Break-Even Analysis ---\n");
+    
+    let principal = get_float_input("Enter original amount of the current loan ($)");
+    let annual_rate = get_float_input("Enter current loan's annual interest rate (as decimal, e.g., 0.05 for 5%)");
+    let years = get_float_input("Enter current loan's term in years");
+    let start = get_date_input("Enter current loan's start date (YYYY-MM-DD)");
+    let refinance_date = get_date_input("Enter refinance date (YYYY-MM-DD)");
+    let new_rate = get_float_input("Enter new loan's annual interest rate (as decimal, e.g., 0.05 for 5%)");
+    let new_years = get_float_input("Enter new loan's term in years");
+    let closing_costs = get_float_input("Enter closing costs ($)");
+    
+    let current = Loan::new(principal, annual_rate, years, start);
+    let proposed = Loan::new(current.balance_on(refinance_date), new_rate, new_years, refinance_date);
+    let comparison = match compare_refinance(&current, &proposed, closing_costs) {
+        Ok(comparison) => comparison,
+        Err(error) => {
+            println!("\nError: {}.\n", error);
+            return;
+        }
+    };
+    
+    println!("\n=== Results ===");
+    println!("Balance Refinanced: {}", format_currency(comparison.current_balance));
+    println!("Current Payment: {} at {}", format_currency(comparison.current_payment), format_percentage(annual_rate));
+    println!("New Payment: {} at {}", format_currency(comparison.new_payment), format_percentage(new_rate));
+    println!("Monthly Savings: {}", format_currency(comparison.payment_savings));
+    println!("Closing Costs: {}", format_currency(closing_costs));
+    match (comparison.break_even_period, comparison.break_even_date) {
+        (Some(period), Some(date)) => println!("Break-Even: month {} ({})", period, date),
+        _ => println!("Break-Even: never"),
+    }
+    println!("Remaining Interest on Current Loan: {}", format_currency(comparison.current_remaining_interest));
+    println!("Total Interest on New Loan: {}", format_currency(comparison.new_total_interest));
+    println!("Lifetime Interest Difference: {}", format_currency(comparison.lifetime_interest_savings));
+    println!("Net Savings After Closing Costs: {}", format_currency(comparison.net_savings));
+    println!();
+}
+
+fn get_accumulation_plan_input() -> AccumulationPlan {
+    let principal = get_float_input("Enter initial principal ($)");
+    let frequency = get_frequency_input("Enter return period (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let contribution = get_float_input("Enter contribution per period ($, 0 for none)");
+    let escalation_rate = get_float_input("Enter yearly increase in contributions (as decimal, e.g., 0.03 for 3%, 0 for none)");
+    let capital_gains_tax = get_float_input("Enter yearly capital gains tax rate (as decimal, e.g., 0.25 for 25%, 0 for none)");
+    
+    AccumulationPlan {
+        principal,
+        contribution,
+        escalation: if escalation_rate != 0.0 { Escalation::Percent(escalation_rate) } else { Escalation::None },
+        frequency,
+        capital_gains_tax,
+    }
+}
+
+fn print_percentile_report(heading: &str, report: &PercentileReport) {
+    println!("{:<12} {:<15}", "Percentile", heading);
+    println!("{:-<28}", "");
+    for (name, value) in [
+        ("Worst", report.min),
+        ("5th", report.p5),
+        ("10th", report.p10),
+        ("25th", report.p25),
+        ("Median", report.median),
+        ("75th", report.p75),
+        ("90th", report.p90),
+        ("95th", report.p95),
+        ("Best", report.max),
+    ] {
+        println!("{:<12} {:<15}", name, format_currency(value));
+    }
+    println!("{:<12} {:<15}", "Mean", format_currency(report.mean));
+}
+
+fn simulate_returns_interactive() {
+    println!("\n--- Monte Carlo Simulation of Random Returns ---\n");
+    
+    let plan = get_accumulation_plan_input();
+    let years = get_float_input("Enter number of years");
+    let distribution = loop {
+        match get_string_input("Choose the return distribution (n=normal, l=lognormal, t=Student-t)").to_lowercase().as_str() {
+            "n" | "normal" => {
+                let mean = get_float_input("Enter mean return per period (as decimal, e.g., 0.002 for 0.2%)");
+                let std_dev = get_float_input("Enter standard deviation of returns per period (as decimal)");
+                break ReturnDistribution::Normal { mean, std_dev };
+            }
+            "l" | "lognormal" => {
+                let log_mean = get_float_input("Enter mean of ln(1 + return) per period");
+                let log_std_dev = get_float_input("Enter standard deviation of ln(1 + return) per period");
+                break ReturnDistribution::LogNormal { log_mean, log_std_dev };
+            }
+            "t" | "student-t" => {
+                let mean = get_float_input("Enter mean return per period (as decimal, e.g., 0.002 for 0.2%)");
+                let scale = get_float_input("Enter scale of returns per period (as decimal)");
+                let degrees_of_freedom = get_float_input("Enter degrees of freedom (e.g., 4; lower means fatter tails)");
+                break ReturnDistribution::StudentT { mean, scale, degrees_of_freedom };
+            }
+            _ => println!("Please enter 'n', 'l' or 't'."),
+        }
+    };
+    let paths = get_u32_input("Enter number of paths to simulate (e.g., 10000)");
+    let seed = get_u32_input("Enter random seed (any whole number; the same seed repeats the results)");
+    let target = get_float_input("Enter target amount ($, 0 for none)");
+    
+    let periods = (years * plan.frequency.periods_per_year() as f64).round() as u32;
+    let simulation = MonteCarlo {
+        plan,
+        distribution,
+        periods,
+        paths,
+        seed: seed as u64,
+        target: if target > 0.0 { Some(target) } else { None },
+    };
+    let report = match simulate(&simulation) {
+        Ok(report) => report,
+        Err(error) => {
+            println!("\nError: {}.\n", error);
+            return;
+        }
+    };
+    
+    println!("\n=== Results ===");
+    println!("Paths Simulated: {}", report.final_amount.count);
+    println!("Periods per Path: {} ({})", periods, simulation.plan.frequency);
+    println!("Total Contributions: {}", format_currency(report.total_contributions));
+    println!();
+    print_percentile_report("Final Amount", &report.final_amount);
+    if let Some(probability) = report.probability_of_target {
+        println!();
+        println!("Chance of reaching {}: {}", format_currency(target), format_percentage(probability));
+    }
+    println!();
+}
+
+fn get_returns_file_input() -> Option<Vec<PeriodReturn>> {
+    let path = get_string_input("Enter path to returns file");
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) => {
+            println!("\nError: could not read {}: {}\n", path, error);
+            return None;
+        }
+    };
+    match parse_returns(&text) {
+        Ok(returns) if returns.is_empty() => {
+            println!("\nError: no returns found in {}\n", path);
+            None
+        }
+        Ok(returns) => Some(returns),
+        Err(error) => {
+            println!("\nError: {}\n", error);
+            None
+        }
+    }
+}
+
+fn backtest_returns_interactive() {
+    println!("\n--- Backtest on Historical Returns ---\n");
+    println!("The file should list one period's return per line, as rate or YYYY-MM-DD,rate");
+    println!("(e.g., 0.012 or 1.2%), one line per week, month or other return period.\n");
+    
+    let history = match get_returns_file_input() {
+        Some(history) => history,
+        None => return,
+    };
+    let plan = get_accumulation_plan_input();
+    let window_years = get_float_input("Enter rolling window length in years (0 for the whole series only)");
+    
+    let returns: Vec<f64> = history.iter().map(|period| period.rate).collect();
+    let label = |start: usize| match history[start].date {
+        Some(date) => date.to_string(),
+        None => format!("period {}", start + 1),
+    };
+    
+    let result = accumulate(&plan, &returns);
+    println!("\n=== Whole Series ===");
+    println!("Periods: {} ({}), from {}", returns.len(), plan.frequency, label(0));
+    println!("Total Contributions: {}", format_currency(result.total_contributions));
+    println!("Profit Before Tax: {}", format_currency(result.total_profit));
+    println!("Tax Paid: {}", format_currency(result.total_tax));
+    println!("Final Amount: {}", format_currency(result.final_amount));
+    
+    if window_years > 0.0 {
+        let window = (window_years * plan.frequency.periods_per_year() as f64).round() as usize;
+        let backtest = match rolling_backtest(&plan, &returns, window) {
+            Ok(backtest) => backtest,
+            Err(error) => {
+                println!("\nError: {}.\n", error);
+                return;
+            }
+        };
+        
+        println!("\n=== Rolling {}-Period Windows ===", window);
+        println!("Windows: {}", backtest.windows.len());
+        println!("{:<8} {:<14} {:<15} {:<15}", "", "Start", "Final Amount", "Tax Paid");
+        println!("{:-<55}", "");
+        for (name, outcome) in [("Best", &backtest.best), ("Median", &backtest.median), ("Worst", &backtest.worst)] {
+            println!(
+                "{:<8} {:<14} {:<15} {:<15}",
+                name,
+                label(outcome.start),
+                format_currency(outcome.result.final_amount),
+                format_currency(outcome.result.total_tax)
+            );
+        }
+        println!();
+        print_percentile_report("Final Amount", &backtest.final_amount);
+    }
+    println!();
+}
+
+fn bootstrap_returns_interactive() {
+    println!("\n--- Bootstrap Resampling of Historical Returns ---\n");
+    println!("The file should list one period's return per line, as rate or YYYY-MM-DD,rate");
+    println!("(e.g., 0.012 or 1.2%), one line per week, month or other return period.\n");
+    
+    let history = match get_returns_file_input() {
+        Some(history) => history,
+        None => return,
+    };
+    let plan = get_accumulation_plan_input();
+    let years = get_float_input("Enter number of years");
+    let block_length = get_u32_input("Enter block length in periods (1 to draw single periods)");
+    let paths = get_u32_input("Enter number of paths to simulate (e.g., 10000)");
+    let seed = get_u32_input("Enter random seed (any whole number; the same seed repeats the results)");
+    let target = get_float_input("Enter target amount ($, 0 for none)");
+    
+    let periods = (years * plan.frequency.periods_per_year() as f64).round() as u32;
+    let simulation = Bootstrap {
+        plan,
+        history: history.iter().map(|period| period.rate).collect(),
+        resampling: if block_length > 1 { Resampling::Blocks(block_length as usize) } else { Resampling::Periods },
+        periods,
+        paths,
+        seed: seed as u64,
+        target: if target > 0.0 { Some(target) } else { None },
+    };
+    let report = match bootstrap(&simulation) {
+        Ok(report) => report,
+        Err(error) => {
+            println!("\nError: {}.\n", error);
+            return;
+        }
+    };
+    
+    println!("\n=== Results ===");
+    println!("Historical Periods: {}", simulation.history.len());
+    println!("Paths Simulated: {}", report.final_amount.count);
+    println!("Periods per Path: {} ({})", periods, simulation.plan.frequency);
+    println!("Total Contributions: {}", format_currency(report.total_contributions));
+    println!();
+    print_percentile_report("Final Amount", &report.final_amount);
+    if let Some(probability) = report.probability_of_target {
+        println!();
+        println!("Chance of reaching {}: {}", format_currency(target), format_percentage(probability));
+    }
+    println!();
+}
+
+fn sequence_risk_interactive() {
+    println!("\n--- Sequence-of-Returns Risk ---\n");
+    println!("The file should list one return per withdrawal period, as rate or YYYY-MM-DD,rate");
+    println!("(e.g., 0.05 or 5%). The plan runs for as many periods as the file holds.\n");
+    
+    let history = match get_returns_file_input() {
+        Some(history) => history,
+        None => return,
+    };
+    let balance = get_float_input("Enter starting balance ($)");
+    let frequency = get_frequency_input("Enter withdrawal frequency, matching the returns (52=weekly, 26=biweekly, 24=semimonthly, 12=monthly, 4=quarterly, 1=annually)");
+    let withdrawal = get_float_input("Enter withdrawal amount per period ($)");
+    let timing = get_timing_input("Are withdrawals taken at the beginning or end of each period? (b/e)");
+    let inflation_rate = get_float_input("Enter yearly inflation increase in withdrawals (as decimal, e.g., 0.03 for 3%, 0 for fixed)");
+    let shuffles = get_u32_input("Enter number of random reorderings to add (0 for none)");
+    let seed = get_u32_input("Enter random seed (any whole number; the same seed repeats the results)");
+    
+    let returns: Vec<f64> = history.iter().map(|period| period.rate).collect();
+    let plan = WithdrawalPlan {
+        balance,
+        annual_rate: 0.0,
+        compounding: Compounding::Annually,
+        withdrawal,
+        frequency,
+        timing,
+        indexation: if inflation_rate != 0.0 { Escalation::Percent(inflation_rate) } else { Escalation::None },
+        years: returns.len() as f64 / frequency.periods_per_year() as f64,
+        inflation_rate,
+    };
+    let risk = match analyze_sequence_risk(&plan, &reorder_returns(&returns, shuffles, seed as u64)) {
+        Ok(risk) => risk,
+        Err(error) => {
+            println!("\nError: {}.\n", error);
+            return;
+        }
+    };
+    
+    println!("\n=== Results ===");
+    println!("Starting Balance: {}", format_currency(balance));
+    println!("Withdrawal: {} ({})", format_currency(withdrawal), frequency);
+    println!("Horizon: {:.1} years", plan.years);
+    println!("Sequences Run Out: {}", format_percentage(risk.depletion_rate));
+    println!("Worst Order: {}", risk.outcomes[risk.worst].label);
+    println!("Best Order: {}", risk.outcomes[risk.best].label);
+    println!();
+    println!("{:<14} {:<18} {:<15} {:<15}", "Order", "Money Lasts", "Withdrawn", "Final Balance");
+    println!("{:-<64}", "");
+    for outcome in &risk.outcomes {
+        let lasts = match outcome.result.years_lasted {
+            Some(years_lasted) => format!("{:.1} years", years_lasted),
+            None => "whole horizon".to_string(),
+        };
+        println!(
+            "{:<14} {:<18} {:<15} {:<15}",
+            outcome.label,
+            lasts,
+            format_currency(outcome.result.total_withdrawn),
+            format_currency(outcome.result.final_balance)
+        );
+    }
+    println!();
+    print_percentile_report("Final Balance", &risk.ending_balance);
+    
+    // Year-end balances for the fixed orderings, laid out like the yearly breakdown
+    let shown = risk.outcomes.len().min(4);
+    println!();
+    print!("{:<6}", "Year");
+    for outcome in &risk.outcomes[..shown] {
+        print!(" {:<15}", outcome.label);
+    }
+    println!();
+    println!("{:-<70}", "");
+    for year in risk.yearly_balances() {
+        print!("{:<6}", year.year);
+        for balance in &year.balances[..shown] {
+            print!(" {:<15}", format_currency(*balance));
+        }
+        println!();
+    }
+    println!();
+}
@@ -0,0 +1,150 @@
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Numeric type the compound interest formulas can be evaluated over
+///
+/// Implemented for `f64`, `f32` and, with the `decimal` feature, `Decimal`.
+/// Downstream crates can implement it for their own numeric types.
+///
+/// The methods of this trait never panic. Floating-point types overflow to
+/// infinity as usual; `Decimal` only holds magnitudes up to about 7.9e28, so
+/// its results beyond that saturate at `Decimal::MAX` (or `Decimal::MIN`) and
+/// results too small to represent become zero. Balances that can grow without
+/// bound are combined with `saturating_add` and `saturating_mul`.
+pub trait Numeric:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The additive identity
+    fn zero() -> Self;
+    /// The multiplicative identity
+    fn one() -> Self;
+    /// Convert from an `f64`, such as a compounding frequency or constant
+    fn from_f64(value: f64) -> Self;
+    /// Convert to an `f64` for display or iteration counts
+    fn to_f64(self) -> f64;
+    /// Raise to a (possibly fractional) power
+    fn powf(self, exponent: Self) -> Self;
+    /// e raised to this value
+    fn exp(self) -> Self;
+    /// Natural logarithm
+    fn ln(self) -> Self;
+
+    /// Add, saturating at the limits of the type instead of overflowing
+    fn saturating_add(self, other: Self) -> Self {
+        self + other
+    }
+
+    /// Multiply, saturating at the limits of the type instead of overflowing
+    fn saturating_mul(self, other: Self) -> Self {
+        self * other
+    }
+}
+
+impl Numeric for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn powf(self, exponent: Self) -> Self {
+        f64::powf(self, exponent)
+    }
+
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+}
+
+impl Numeric for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn powf(self, exponent: Self) -> Self {
+        f32::powf(self, exponent)
+    }
+
+    fn exp(self) -> Self {
+        f32::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        f32::ln(self)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Numeric for rust_decimal::Decimal {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    fn from_f64(value: f64) -> Self {
+        rust_decimal::prelude::FromPrimitive::from_f64(value).unwrap_or_default()
+    }
+
+    fn to_f64(self) -> f64 {
+        rust_decimal::prelude::ToPrimitive::to_f64(&self).unwrap_or_default()
+    }
+
+    fn powf(self, exponent: Self) -> Self {
+        let grows = (self > Self::ONE) == (exponent > Self::ZERO);
+        rust_decimal::MathematicalOps::checked_powd(&self, exponent)
+            .unwrap_or(if grows { Self::MAX } else { Self::ZERO })
+    }
+
+    fn exp(self) -> Self {
+        rust_decimal::MathematicalOps::checked_exp(&self).unwrap_or(if self > Self::ZERO { Self::MAX } else { Self::ZERO })
+    }
+
+    /// The logarithm of zero or a negative number saturates at `Decimal::MIN`
+    fn ln(self) -> Self {
+        rust_decimal::MathematicalOps::checked_ln(&self).unwrap_or(Self::MIN)
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        rust_decimal::Decimal::saturating_add(self, other)
+    }
+
+    fn saturating_mul(self, other: Self) -> Self {
+        rust_decimal::Decimal::saturating_mul(self, other)
+    }
+}
@@ -0,0 +1,142 @@
+//! Seedable pseudo-random numbers for the simulations.
+//!
+//! The generator is xoshiro256** seeded through SplitMix64, so a given seed
+//! produces the same sequence on every platform.
+
+/// Advance a SplitMix64 state and return its next output
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// xoshiro256** generator
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// A generator whose sequence is fixed by `seed`
+    pub(crate) fn new(seed: u64) -> Self {
+        let mut seeder = seed;
+        Rng {
+            state: [
+                splitmix64(&mut seeder),
+                splitmix64(&mut seeder),
+                splitmix64(&mut seeder),
+                splitmix64(&mut seeder),
+            ],
+        }
+    }
+
+    /// Next raw 64-bit output
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let shifted = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= shifted;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    /// Uniform draw from [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill the mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform draw from 0 up to but not including `bound`
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        // Multiply-shift maps the full 64-bit range onto the bound
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Standard normal draw, by the Box-Muller transform
+    pub(crate) fn standard_normal(&mut self) -> f64 {
+        // 1 - u lies in (0, 1], so the logarithm is finite
+        let radius = (-2.0 * (1.0 - self.next_f64()).ln()).sqrt();
+        radius * (2.0 * std::f64::consts::PI * self.next_f64()).cos()
+    }
+
+    /// Gamma draw with the given shape and unit scale, by Marsaglia and Tsang's method
+    pub(crate) fn gamma(&mut self, shape: f64) -> f64 {
+        if shape < 1.0 {
+            // Boost the shape above 1 and scale the draw back down
+            return self.gamma(shape + 1.0) * (1.0 - self.next_f64()).powf(1.0 / shape);
+        }
+
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let x = self.standard_normal();
+            let v = (1.0 + c * x).powi(3);
+            if v <= 0.0 {
+                continue;
+            }
+            let u = 1.0 - self.next_f64();
+            if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+                return d * v;
+            }
+        }
+    }
+
+    /// Student's t draw with the given degrees of freedom
+    pub(crate) fn student_t(&mut self, degrees_of_freedom: f64) -> f64 {
+        let chi_square = 2.0 * self.gamma(degrees_of_freedom / 2.0);
+        self.standard_normal() / (chi_square / degrees_of_freedom).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_and_variance(draws: &[f64]) -> (f64, f64) {
+        let mean = draws.iter().sum::<f64>() / draws.len() as f64;
+        let variance = draws.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / draws.len() as f64;
+        (mean, variance)
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first, (0..5).map(|_| c.next_u64()).collect::<Vec<_>>());
+
+        let mut rng = Rng::new(7);
+        assert!((0..1000).map(|_| rng.next_f64()).all(|x| (0.0..1.0).contains(&x)));
+        assert!((0..1000).map(|_| rng.below(10)).all(|i| i < 10));
+    }
+
+    #[test]
+    fn test_distribution_moments() {
+        let mut rng = Rng::new(1);
+        let normal: Vec<f64> = (0..100_000).map(|_| rng.standard_normal()).collect();
+        let (mean, variance) = mean_and_variance(&normal);
+        assert!(mean.abs() < 0.02);
+        assert!((variance - 1.0).abs() < 0.02);
+
+        // Gamma(k) has mean and variance k
+        let gamma: Vec<f64> = (0..100_000).map(|_| rng.gamma(2.5)).collect();
+        let (mean, variance) = mean_and_variance(&gamma);
+        assert!((mean - 2.5).abs() < 0.05);
+        assert!((variance - 2.5).abs() < 0.1);
+
+        // Student's t with 5 degrees of freedom has variance 5/3
+        let t: Vec<f64> = (0..100_000).map(|_| rng.student_t(5.0)).collect();
+        let (mean, variance) = mean_and_variance(&t);
+        assert!(mean.abs() < 0.02);
+        assert!((variance - 5.0 / 3.0).abs() < 0.1);
+    }
+}
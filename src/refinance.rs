@@ -0,0 +1,128 @@
+use crate::date::Date;
+use crate::error::CalcError;
+use crate::loan::{amortization_schedule, Loan};
+
+/// Comparison of keeping an existing loan against refinancing it
+#[derive(Debug, Clone)]
+pub struct RefinanceComparison {
+    /// Balance of the existing loan when the new loan starts
+    pub current_balance: f64,
+    /// Next payment on the existing loan
+    pub current_payment: f64,
+    /// Regular payment on the new loan
+    pub new_payment: f64,
+    /// Saving per payment period from switching (negative if the new payment is higher)
+    pub payment_savings: f64,
+    /// Costs paid up front to refinance
+    pub closing_costs: f64,
+    /// Payment number on the new loan by which the savings have covered the closing costs
+    pub break_even_period: Option<u32>,
+    /// Date of the break-even payment
+    pub break_even_date: Option<Date>,
+    /// Interest left to pay on the existing loan
+    pub current_remaining_interest: f64,
+    /// Interest paid over the life of the new loan
+    pub new_total_interest: f64,
+    /// Interest saved over the life of the loans (negative if refinancing costs more interest)
+    pub lifetime_interest_savings: f64,
+    /// Everything saved over the life of the loans once the closing costs are paid
+    pub net_savings: f64,
+}
+
+/// Compare the rest of an existing loan with a proposed loan that replaces it
+///
+/// The proposed loan starts on the refinance date, usually with the existing
+/// loan's balance on that date (see `Loan::balance_on`) as its principal. The
+/// loans are compared payment by payment, so both must use the same payment
+/// frequency; once one loan is paid off its payments count as zero.
+pub fn compare_refinance(current: &Loan, proposed: &Loan, closing_costs: f64) -> Result<RefinanceComparison, CalcError> {
+    if current.frequency != proposed.frequency {
+        return Err(CalcError::InvalidInput("both loans must use the same payment frequency".to_string()));
+    }
+
+    let refinance_date = proposed.start;
+    let current_schedule = amortization_schedule(current);
+    let new_schedule = amortization_schedule(proposed);
+    let remaining: Vec<_> = current_schedule.rows.iter().filter(|row| row.date > refinance_date).collect();
+
+    let current_payments: Vec<f64> = remaining.iter().map(|row| row.payment + row.extra).collect();
+    let new_payments: Vec<f64> = new_schedule.rows.iter().map(|row| row.payment + row.extra).collect();
+
+    let mut break_even_period = None;
+    let mut cumulative_savings = 0.0;
+    for period in 0..current_payments.len().max(new_payments.len()) {
+        let current_payment = current_payments.get(period).copied().unwrap_or(0.0);
+        let new_payment = new_payments.get(period).copied().unwrap_or(0.0);
+        cumulative_savings += current_payment - new_payment;
+        if cumulative_savings >= closing_costs {
+            break_even_period = Some(period as u32 + 1);
+            break;
+        }
+    }
+
+    let current_payment = current_payments.first().copied().unwrap_or(0.0);
+    let new_payment = new_schedule.payment;
+    let current_remaining_interest: f64 = remaining.iter().map(|row| row.interest).sum();
+    let current_remaining_paid: f64 = current_payments.iter().sum();
+
+    Ok(RefinanceComparison {
+        current_balance: current.balance_on(refinance_date),
+        current_payment,
+        new_payment,
+        payment_savings: current_payment - new_payment,
+        closing_costs,
+        break_even_period,
+        break_even_date: break_even_period.map(|period| proposed.payment_date(period)),
+        current_remaining_interest,
+        new_total_interest: new_schedule.total_interest,
+        lifetime_interest_savings: current_remaining_interest - new_schedule.total_interest,
+        net_savings: current_remaining_paid - new_schedule.total_paid - closing_costs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PaymentFrequency;
+
+    fn date(y: i32, m: u32, d: u32) -> Date {
+        Date::from_ymd(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_refinance_to_lower_rate() {
+        let current = Loan::new(300000.0, 0.07, 30.0, date(2020, 1, 1));
+        let refinance_date = date(2025, 1, 1);
+        let balance = current.balance_on(refinance_date);
+        let proposed = Loan::new(balance, 0.055, 25.0, refinance_date);
+
+        let comparison = compare_refinance(&current, &proposed, 4000.0).unwrap();
+        assert_eq!(comparison.current_balance, balance);
+        assert!((comparison.current_payment - current.payment()).abs() < 1e-9);
+        assert!(comparison.payment_savings > 0.0);
+
+        // Level savings each month, so break-even is the first month they cover the costs
+        let expected = (4000.0 / comparison.payment_savings).ceil() as u32;
+        assert_eq!(comparison.break_even_period, Some(expected));
+        assert_eq!(comparison.break_even_date, Some(proposed.payment_date(expected)));
+        assert!(comparison.lifetime_interest_savings > 0.0);
+        assert!(comparison.net_savings > 0.0);
+    }
+
+    #[test]
+    fn test_refinance_that_never_breaks_even() {
+        let current = Loan::new(100000.0, 0.05, 15.0, date(2020, 1, 1));
+        let refinance_date = date(2024, 1, 1);
+        let proposed = Loan::new(current.balance_on(refinance_date), 0.06, 11.0, refinance_date);
+
+        let comparison = compare_refinance(&current, &proposed, 2000.0).unwrap();
+        assert_eq!(comparison.break_even_period, None);
+        assert!(comparison.lifetime_interest_savings < 0.0);
+
+        let weekly = Loan {
+            frequency: PaymentFrequency::Weekly,
+            ..proposed
+        };
+        assert!(compare_refinance(&current, &weekly, 2000.0).is_err());
+    }
+}
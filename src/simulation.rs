@@ -0,0 +1,386 @@
+use crate::accumulation::{accumulate, AccumulationPlan};
+use crate::error::CalcError;
+use crate::random::Rng;
+
+/// Distribution that each period's return is drawn from
+///
+/// Parameters are per period, matching the plan's frequency, and returns are
+/// decimals (0.01 for 1%).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReturnDistribution {
+    /// Normally distributed returns
+    Normal { mean: f64, std_dev: f64 },
+    /// Returns whose logarithm, ln(1 + r), is normally distributed; never below -100%
+    LogNormal { log_mean: f64, log_std_dev: f64 },
+    /// Fat-tailed returns, `mean + scale * t`, with variance scale² ν / (ν - 2) when ν > 2
+    StudentT { mean: f64, scale: f64, degrees_of_freedom: f64 },
+}
+
+impl ReturnDistribution {
+    fn validate(&self) -> Result<(), CalcError> {
+        let spread = match *self {
+            ReturnDistribution::Normal { std_dev, .. } => std_dev,
+            ReturnDistribution::LogNormal { log_std_dev, .. } => log_std_dev,
+            ReturnDistribution::StudentT { scale, degrees_of_freedom, .. } => {
+                if degrees_of_freedom.is_nan() || degrees_of_freedom <= 0.0 {
+                    return Err(CalcError::InvalidInput("degrees of freedom must be positive".to_string()));
+                }
+                scale
+            }
+        };
+        if spread < 0.0 {
+            return Err(CalcError::InvalidInput("the spread of returns must not be negative".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Draw one period's return
+    pub(crate) fn sample(&self, rng: &mut Rng) -> f64 {
+        match *self {
+            ReturnDistribution::Normal { mean, std_dev } => mean + std_dev * rng.standard_normal(),
+            ReturnDistribution::LogNormal { log_mean, log_std_dev } => {
+                (log_mean + log_std_dev * rng.standard_normal()).exp() - 1.0
+            }
+            ReturnDistribution::StudentT { mean, scale, degrees_of_freedom } => {
+                mean + scale * rng.student_t(degrees_of_freedom)
+            }
+        }
+    }
+}
+
+/// A Monte Carlo simulation of an accumulation plan under random returns
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    /// Contributions and tax rules applied on every path
+    pub plan: AccumulationPlan,
+    /// Distribution each period's return is drawn from
+    pub distribution: ReturnDistribution,
+    /// Number of periods on each path
+    pub periods: u32,
+    /// Number of paths to simulate
+    pub paths: u32,
+    /// Seed for the random numbers; the same seed always gives the same report
+    pub seed: u64,
+    /// Final amount whose chance of being reached is reported, if any
+    pub target: Option<f64>,
+}
+
+/// Distribution of outcomes across simulated or historical paths
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercentileReport {
+    /// Number of outcomes summarized
+    pub count: usize,
+    /// Average outcome
+    pub mean: f64,
+    /// Worst outcome
+    pub min: f64,
+    /// 5th percentile
+    pub p5: f64,
+    /// 10th percentile
+    pub p10: f64,
+    /// 25th percentile
+    pub p25: f64,
+    /// Median outcome
+    pub median: f64,
+    /// 75th percentile
+    pub p75: f64,
+    /// 90th percentile
+    pub p90: f64,
+    /// 95th percentile
+    pub p95: f64,
+    /// Best outcome
+    pub max: f64,
+}
+
+impl PercentileReport {
+    /// Summarize a set of outcomes, or `None` if there are none
+    ///
+    /// Percentiles interpolate linearly between the nearest outcomes.
+    pub fn from_outcomes(outcomes: &[f64]) -> Option<Self> {
+        if outcomes.is_empty() {
+            return None;
+        }
+        let mut sorted = outcomes.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let position = p * (sorted.len() - 1) as f64;
+            let below = position.floor() as usize;
+            let above = position.ceil() as usize;
+            sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+        };
+
+        Some(PercentileReport {
+            count: sorted.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            min: sorted[0],
+            p5: percentile(0.05),
+            p10: percentile(0.10),
+            p25: percentile(0.25),
+            median: percentile(0.50),
+            p75: percentile(0.75),
+            p90: percentile(0.90),
+            p95: percentile(0.95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Result of a Monte Carlo simulation
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    /// Distribution of the final amount after tax
+    pub final_amount: PercentileReport,
+    /// Share of paths that reached the target, if one was given
+    pub probability_of_target: Option<f64>,
+    /// Total amount contributed on each path
+    pub total_contributions: f64,
+}
+
+/// Share of outcomes at or above `target`
+fn probability_of_reaching(outcomes: &[f64], target: f64) -> f64 {
+    outcomes.iter().filter(|&&outcome| outcome >= target).count() as f64 / outcomes.len() as f64
+}
+
+/// Run `paths` paths of a plan, filling each path's returns with `draw`
+fn run_paths<F: FnMut(&mut Rng, &mut [f64])>(
+    plan: &AccumulationPlan,
+    periods: u32,
+    paths: u32,
+    seed: u64,
+    target: Option<f64>,
+    mut draw: F,
+) -> Result<SimulationReport, CalcError> {
+    if paths == 0 {
+        return Err(CalcError::InvalidInput("at least one path is needed".to_string()));
+    }
+    plan.validate()?;
+
+    let mut rng = Rng::new(seed);
+    let mut returns = vec![0.0; periods as usize];
+    let mut outcomes = Vec::with_capacity(paths as usize);
+    let mut total_contributions = 0.0;
+    for _ in 0..paths {
+        draw(&mut rng, &mut returns);
+        let result = accumulate(plan, &returns);
+        total_contributions = result.total_contributions;
+        outcomes.push(result.final_amount);
+    }
+
+    Ok(SimulationReport {
+        final_amount: PercentileReport::from_outcomes(&outcomes).ok_or(CalcError::Unreachable)?,
+        probability_of_target: target.map(|target| probability_of_reaching(&outcomes, target)),
+        total_contributions,
+    })
+}
+
+/// Run a Monte Carlo simulation of an accumulation plan
+///
+/// Every path draws its own sequence of returns and runs it through
+/// `accumulate`, so contributions and yearly tax follow the same rules as
+/// `calculate_weekly_with_yearly_tax`.
+pub fn simulate(simulation: &MonteCarlo) -> Result<SimulationReport, CalcError> {
+    simulation.distribution.validate()?;
+    let distribution = simulation.distribution;
+    run_paths(
+        &simulation.plan,
+        simulation.periods,
+        simulation.paths,
+        simulation.seed,
+        simulation.target,
+        |rng, returns| {
+            for rate in returns.iter_mut() {
+                *rate = distribution.sample(rng);
+            }
+        },
+    )
+}
+
+/// How periods are drawn from a historical return series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resampling {
+    /// Each period is drawn independently
+    Periods,
+    /// Runs of consecutive periods are drawn together, keeping the
+    /// autocorrelation within each block; blocks wrap around the end of the series
+    Blocks(usize),
+}
+
+/// A bootstrap simulation that resamples a historical return series
+#[derive(Debug, Clone)]
+pub struct Bootstrap {
+    /// Contributions and tax rules applied on every path
+    pub plan: AccumulationPlan,
+    /// Historical return of each period, at the plan's frequency
+    pub history: Vec<f64>,
+    /// Whether single periods or blocks are drawn
+    pub resampling: Resampling,
+    /// Number of periods on each path
+    pub periods: u32,
+    /// Number of paths to simulate
+    pub paths: u32,
+    /// Seed for the random numbers; the same seed always gives the same report
+    pub seed: u64,
+    /// Final amount whose chance of being reached is reported, if any
+    pub target: Option<f64>,
+}
+
+/// Run a bootstrap simulation of an accumulation plan
+///
+/// Each path is built by drawing periods, or blocks of periods, from the
+/// history with replacement, so the fat tails and streaks of the real series
+/// carry over. The paths then run through `accumulate` as in `simulate`.
+pub fn bootstrap(simulation: &Bootstrap) -> Result<SimulationReport, CalcError> {
+    let history = &simulation.history;
+    if history.is_empty() {
+        return Err(CalcError::InvalidInput("the return history is empty".to_string()));
+    }
+    let block_length = match simulation.resampling {
+        Resampling::Periods => 1,
+        Resampling::Blocks(length) if (1..=history.len()).contains(&length) => length,
+        Resampling::Blocks(_) => {
+            return Err(CalcError::InvalidInput(format!(
+                "the block length must be between 1 and the {} periods in the history",
+                history.len()
+            )))
+        }
+    };
+
+    run_paths(
+        &simulation.plan,
+        simulation.periods,
+        simulation.paths,
+        simulation.seed,
+        simulation.target,
+        |rng, returns| {
+            for block in returns.chunks_mut(block_length) {
+                let start = rng.below(history.len());
+                for (offset, rate) in block.iter_mut().enumerate() {
+                    *rate = history[(start + offset) % history.len()];
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Escalation, PaymentFrequency};
+
+    fn simulation(distribution: ReturnDistribution) -> MonteCarlo {
+        MonteCarlo {
+            plan: AccumulationPlan {
+                principal: 10000.0,
+                contribution: 100.0,
+                escalation: Escalation::None,
+                frequency: PaymentFrequency::Weekly,
+                capital_gains_tax: 0.25,
+            },
+            distribution,
+            periods: 52 * 5,
+            paths: 2000,
+            seed: 2024,
+            target: Some(40000.0),
+        }
+    }
+
+    #[test]
+    fn test_percentile_report() {
+        let report = PercentileReport::from_outcomes(&[5.0, 1.0, 4.0, 2.0, 3.0]).unwrap();
+        assert_eq!(report.count, 5);
+        assert_eq!((report.min, report.median, report.max), (1.0, 3.0, 5.0));
+        assert_eq!(report.mean, 3.0);
+        assert!((report.p25 - 2.0).abs() < 1e-12);
+        assert!((report.p90 - 4.6).abs() < 1e-12);
+        assert_eq!(PercentileReport::from_outcomes(&[]), None);
+    }
+
+    #[test]
+    fn test_zero_volatility_matches_deterministic_model() {
+        let report = simulate(&simulation(ReturnDistribution::Normal { mean: 0.002, std_dev: 0.0 })).unwrap();
+        let (final_amount, _, _) = crate::calculate_weekly_with_yearly_tax(10000.0, 0.002, 52 * 5, 100.0, 0.25);
+
+        assert!((report.final_amount.min - final_amount).abs() < 1e-6);
+        assert!((report.final_amount.max - final_amount).abs() < 1e-6);
+        assert!((report.total_contributions - 26000.0).abs() < 1e-9);
+        // Every path is the same, and all of them clear the target
+        assert!(final_amount > 40000.0);
+        assert_eq!(report.probability_of_target, Some(1.0));
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible_and_spread_out() {
+        let distributions = [
+            ReturnDistribution::Normal { mean: 0.002, std_dev: 0.02 },
+            ReturnDistribution::LogNormal { log_mean: 0.0018, log_std_dev: 0.02 },
+            ReturnDistribution::StudentT { mean: 0.002, scale: 0.015, degrees_of_freedom: 4.0 },
+        ];
+        for distribution in distributions {
+            let report = simulate(&simulation(distribution)).unwrap();
+            let again = simulate(&simulation(distribution)).unwrap();
+            assert_eq!(report.final_amount, again.final_amount);
+
+            let outcomes = &report.final_amount;
+            assert!(outcomes.p5 < outcomes.median && outcomes.median < outcomes.p95);
+            let probability = report.probability_of_target.unwrap();
+            assert!(probability > 0.0 && probability < 1.0);
+        }
+
+        let mut other_seed = simulation(distributions[0]);
+        other_seed.seed = 1;
+        assert_ne!(simulate(&other_seed).unwrap().final_amount, simulate(&simulation(distributions[0])).unwrap().final_amount);
+    }
+
+    fn bootstrap_simulation(history: Vec<f64>, resampling: Resampling) -> Bootstrap {
+        Bootstrap {
+            plan: AccumulationPlan {
+                principal: 1000.0,
+                contribution: 0.0,
+                escalation: Escalation::None,
+                frequency: PaymentFrequency::Monthly,
+                capital_gains_tax: 0.0,
+            },
+            history,
+            resampling,
+            periods: 2,
+            paths: 500,
+            seed: 9,
+            target: Some(1000.0),
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_periods_and_blocks() {
+        // Single periods mix the up and down months freely
+        let report = bootstrap(&bootstrap_simulation(vec![0.1, -0.1], Resampling::Periods)).unwrap();
+        assert!((report.final_amount.min - 810.0).abs() < 1e-9);
+        assert!((report.final_amount.max - 1210.0).abs() < 1e-9);
+        let probability = report.probability_of_target.unwrap();
+        assert!(probability > 0.1 && probability < 0.4);
+
+        // Whole blocks always hold one of each, so every path ends at 990
+        let report = bootstrap(&bootstrap_simulation(vec![0.1, -0.1], Resampling::Blocks(2))).unwrap();
+        assert!((report.final_amount.min - 990.0).abs() < 1e-9);
+        assert!((report.final_amount.max - 990.0).abs() < 1e-9);
+        assert_eq!(report.probability_of_target, Some(0.0));
+
+        let again = bootstrap(&bootstrap_simulation(vec![0.1, -0.1], Resampling::Periods)).unwrap();
+        let first = bootstrap(&bootstrap_simulation(vec![0.1, -0.1], Resampling::Periods)).unwrap();
+        assert_eq!(first.final_amount, again.final_amount);
+
+        assert!(bootstrap(&bootstrap_simulation(Vec::new(), Resampling::Periods)).is_err());
+        assert!(bootstrap(&bootstrap_simulation(vec![0.1, -0.1], Resampling::Blocks(3))).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_simulations() {
+        let mut invalid = simulation(ReturnDistribution::Normal { mean: 0.0, std_dev: -0.01 });
+        assert!(simulate(&invalid).is_err());
+        invalid.distribution = ReturnDistribution::Normal { mean: 0.0, std_dev: 0.01 };
+        invalid.paths = 0;
+        assert!(simulate(&invalid).is_err());
+        invalid.paths = 10;
+        invalid.distribution = ReturnDistribution::StudentT { mean: 0.0, scale: 0.01, degrees_of_freedom: f64::NAN };
+        assert!(simulate(&invalid).is_err());
+    }
+}
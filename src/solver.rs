@@ -0,0 +1,134 @@
+//! Numeric root finding shared by the solvers.
+
+use crate::error::CalcError;
+
+/// Find the smallest `x` in `[0, max]` where an increasing function `f` reaches zero
+///
+/// The upper bound starts at `initial` and doubles until `f` is no longer
+/// negative. Returns `None` if that never happens before `max`.
+pub(crate) fn solve_increasing<F: Fn(f64) -> f64>(f: F, initial: f64, max: f64, tolerance: f64) -> Option<f64> {
+    let mut low = 0.0;
+    let mut high = initial;
+    while f(high) < 0.0 {
+        if high >= max {
+            return None;
+        }
+        low = high;
+        high = (high * 2.0).min(max);
+    }
+
+    while high - low > tolerance {
+        let mid = (low + high) / 2.0;
+        if f(mid) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(high)
+}
+
+/// Most iterations `ridders` takes before reporting `NoConvergence`
+const MAX_ROOT_ITERATIONS: u32 = 200;
+
+/// Find the root of an increasing function `f`, searching upwards from `low`
+///
+/// The upper end of the bracket starts at `high` and doubles, up to `max`, until
+/// `f` changes sign. The bracket is then narrowed with `ridders`, which cannot
+/// stall on steep curves the way secant steps can.
+pub(crate) fn find_root_increasing<F: Fn(f64) -> f64>(
+    f: F,
+    low: f64,
+    high: f64,
+    max: f64,
+    tolerance: f64,
+) -> Result<f64, CalcError> {
+    let f_low = f(low);
+    if f_low > 0.0 {
+        return Err(CalcError::Unreachable);
+    }
+    if f_low == 0.0 {
+        return Ok(low);
+    }
+
+    let (mut a, mut b) = (low, high);
+    while f(b) < 0.0 {
+        if b >= max {
+            return Err(CalcError::Unreachable);
+        }
+        a = b;
+        b = (b * 2.0).min(max);
+    }
+    ridders(f, a, b, tolerance)
+}
+
+/// Narrow the bracket `[a, b]`, where `f` changes sign, onto a root with Ridders'
+/// method, which converges quickly on smooth functions and at least halves the
+/// bracket on every step
+pub(crate) fn ridders<F: Fn(f64) -> f64>(f: F, mut a: f64, mut b: f64, tolerance: f64) -> Result<f64, CalcError> {
+    let (mut fa, mut fb) = (f(a), f(b));
+    if !fa.is_finite() || !fb.is_finite() || fa * fb > 0.0 {
+        return Err(CalcError::NoConvergence);
+    }
+    if fa == 0.0 {
+        return Ok(a);
+    }
+    if fb == 0.0 {
+        return Ok(b);
+    }
+
+    for _ in 0..MAX_ROOT_ITERATIONS {
+        if (b - a).abs() < tolerance {
+            return Ok((a + b) / 2.0);
+        }
+
+        let m = (a + b) / 2.0;
+        let fm = f(m);
+        let s = (fm * fm - fa * fb).sqrt();
+        if fm == 0.0 || s == 0.0 {
+            return Ok(m);
+        }
+        let x = m + (m - a) * (fa - fb).signum() * fm / s;
+        let fx = f(x);
+        if !fm.is_finite() || !fx.is_finite() {
+            return Err(CalcError::NoConvergence);
+        }
+        if fx == 0.0 {
+            return Ok(x);
+        }
+
+        // Keep the tightest bracket out of the four points
+        if fm.signum() != fx.signum() {
+            (a, fa, b, fb) = (m, fm, x, fx);
+        } else if fa.signum() != fx.signum() {
+            (b, fb) = (x, fx);
+        } else {
+            (a, fa) = (x, fx);
+        }
+    }
+
+    Err(CalcError::NoConvergence)
+}
+
+/// Find a root of `f` with Newton's method from `guess`, using a central
+/// difference for the derivative
+///
+/// This is the unbracketed iteration spreadsheets use for RATE, so it can fail
+/// where `find_root_increasing` would not; callers that need to match
+/// spreadsheet answers use it for that reason.
+pub(crate) fn newton<F: Fn(f64) -> f64>(f: F, guess: f64, tolerance: f64, max_iterations: u32) -> Result<f64, CalcError> {
+    let mut x = guess;
+    for _ in 0..max_iterations {
+        let step = 1e-6 * x.abs().max(1e-3);
+        let derivative = (f(x + step) - f(x - step)) / (2.0 * step);
+        let next = x - f(x) / derivative;
+        if !next.is_finite() {
+            return Err(CalcError::NoConvergence);
+        }
+        if (next - x).abs() < tolerance {
+            return Ok(next);
+        }
+        x = next;
+    }
+    Err(CalcError::NoConvergence)
+}
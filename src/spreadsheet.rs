@@ -0,0 +1,205 @@
+//! Financial functions that match Excel and LibreOffice Calc.
+//!
+//! Arguments come in the same order as the spreadsheet functions, with the
+//! optional ones made explicit. Cash follows the spreadsheet sign convention:
+//! money paid out is negative and money received is positive, so a loan taken
+//! (`pv > 0`) is repaid with negative payments. The `type` argument is a
+//! `ContributionTiming`, where `End` is 0 and `Beginning` is 1.
+
+use crate::error::CalcError;
+use crate::{solver, Compounding, ContributionTiming};
+
+/// Iterations RATE is allowed before giving up, as in Excel
+const RATE_MAX_ITERATIONS: u32 = 20;
+
+/// Change between iterations at which RATE is considered converged, as in Excel
+const RATE_TOLERANCE: f64 = 1e-7;
+
+/// Growth of one unit over `nper` periods at `rate` per period
+fn growth(rate: f64, nper: f64) -> f64 {
+    // One compounding per period, so the period rate compounds like an annual rate
+    Compounding::Annually.growth_factor(rate, nper)
+}
+
+/// Multiplier for payments made at the beginning of each period
+fn timing_factor(rate: f64, timing: ContributionTiming) -> f64 {
+    match timing {
+        ContributionTiming::Beginning => 1.0 + rate,
+        ContributionTiming::End => 1.0,
+    }
+}
+
+/// Sum of the discounted cash flows; zero when the arguments are consistent
+fn tvm_balance(rate: f64, nper: f64, pmt: f64, pv: f64, fv: f64, timing: ContributionTiming) -> f64 {
+    if rate == 0.0 {
+        return pv + pmt * nper + fv;
+    }
+    let growth = growth(rate, nper);
+    pv * growth + pmt * timing_factor(rate, timing) * (growth - 1.0) / rate + fv
+}
+
+/// FV: future value of an investment with periodic payments
+pub fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, timing: ContributionTiming) -> f64 {
+    -tvm_balance(rate, nper, pmt, pv, 0.0, timing)
+}
+
+/// PV: present value of a series of future payments
+pub fn pv(rate: f64, nper: f64, pmt: f64, fv: f64, timing: ContributionTiming) -> f64 {
+    if rate == 0.0 {
+        return -(fv + pmt * nper);
+    }
+    -tvm_balance(rate, nper, pmt, 0.0, fv, timing) / growth(rate, nper)
+}
+
+/// PMT: payment per period for a loan or investment
+pub fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, timing: ContributionTiming) -> Result<f64, CalcError> {
+    if nper == 0.0 {
+        return Err(CalcError::InvalidInput("nper must not be zero".to_string()));
+    }
+    if rate == 0.0 {
+        return Ok(-(pv + fv) / nper);
+    }
+    let growth = growth(rate, nper);
+    Ok(-(pv * growth + fv) * rate / (timing_factor(rate, timing) * (growth - 1.0)))
+}
+
+/// NPER: number of periods needed for the given payments
+pub fn nper(rate: f64, pmt: f64, pv: f64, fv: f64, timing: ContributionTiming) -> Result<f64, CalcError> {
+    if rate == 0.0 {
+        if pmt == 0.0 {
+            return Err(CalcError::InvalidInput("pmt must not be zero when rate is zero".to_string()));
+        }
+        return Ok(-(pv + fv) / pmt);
+    }
+    let adjusted_payment = pmt * timing_factor(rate, timing);
+    let ratio = (adjusted_payment - fv * rate) / (adjusted_payment + pv * rate);
+    if ratio <= 0.0 || rate <= -1.0 {
+        return Err(CalcError::Unreachable);
+    }
+    Ok(ratio.ln() / (1.0 + rate).ln())
+}
+
+/// RATE: interest rate per period, found by Newton's method from `guess`
+///
+/// Like the spreadsheet function, this gives up with `CalcError::NoConvergence`
+/// if the iteration has not settled within 20 steps. Spreadsheets default
+/// `guess` to 0.1.
+pub fn rate(nper: f64, pmt: f64, pv: f64, fv: f64, timing: ContributionTiming, guess: f64) -> Result<f64, CalcError> {
+    if nper <= 0.0 {
+        return Err(CalcError::InvalidInput("nper must be positive".to_string()));
+    }
+    solver::newton(
+        |rate| tvm_balance(rate, nper, pmt, pv, fv, timing),
+        guess,
+        RATE_TOLERANCE,
+        RATE_MAX_ITERATIONS,
+    )
+}
+
+/// IPMT: interest part of the payment in period `per`, counting from 1
+pub fn ipmt(rate: f64, per: f64, nper: f64, pv: f64, fv: f64, timing: ContributionTiming) -> Result<f64, CalcError> {
+    if per < 1.0 || per > nper {
+        return Err(CalcError::InvalidInput("per must be between 1 and nper".to_string()));
+    }
+    let payment = pmt(rate, nper, pv, fv, timing)?;
+    // Interest on the balance carried into period `per`
+    let interest = self::fv(rate, per - 1.0, payment, pv, timing) * rate;
+    match timing {
+        // The first payment is made before any interest has accrued, and later
+        // ones pay the interest of the period before, discounted by one period
+        ContributionTiming::Beginning if per == 1.0 => Ok(0.0),
+        ContributionTiming::Beginning => Ok(interest / (1.0 + rate)),
+        ContributionTiming::End => Ok(interest),
+    }
+}
+
+/// PPMT: principal part of the payment in period `per`, counting from 1
+pub fn ppmt(rate: f64, per: f64, nper: f64, pv: f64, fv: f64, timing: ContributionTiming) -> Result<f64, CalcError> {
+    let interest = ipmt(rate, per, nper, pv, fv, timing)?;
+    Ok(pmt(rate, nper, pv, fv, timing)? - interest)
+}
+
+/// EFFECT: effective annual rate of a nominal rate compounded `npery` times a year
+///
+/// `npery` is truncated to a whole number, as in the spreadsheet function.
+pub fn effect(nominal_rate: f64, npery: f64) -> Result<f64, CalcError> {
+    if nominal_rate <= 0.0 || npery < 1.0 {
+        return Err(CalcError::InvalidInput("nominal_rate must be positive and npery at least 1".to_string()));
+    }
+    Ok(Compounding::PerYear(npery as u32).effective_annual_rate(nominal_rate))
+}
+
+/// NOMINAL: nominal annual rate compounded `npery` times a year that gives `effect_rate`
+///
+/// `npery` is truncated to a whole number, as in the spreadsheet function.
+pub fn nominal(effect_rate: f64, npery: f64) -> Result<f64, CalcError> {
+    if effect_rate <= 0.0 || npery < 1.0 {
+        return Err(CalcError::InvalidInput("effect_rate must be positive and npery at least 1".to_string()));
+    }
+    let npery = npery.trunc();
+    Ok(Compounding::Annually.equivalent_periodic_rate(effect_rate, npery) * npery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ContributionTiming::{Beginning, End};
+
+    // Expected values are the examples from the Excel function reference
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_fv_and_pv() {
+        assert_close(fv(0.06 / 12.0, 10.0, -200.0, -500.0, Beginning), 2581.40, 0.005);
+        assert_close(fv(0.12 / 12.0, 12.0, -1000.0, 0.0, End), 12682.50, 0.005);
+        assert_close(fv(0.11 / 12.0, 35.0, -2000.0, 0.0, Beginning), 82846.25, 0.005);
+        assert_close(fv(0.0, 10.0, -100.0, -1000.0, End), 2000.0, 1e-9);
+
+        assert_close(pv(0.08 / 12.0, 12.0 * 20.0, 500.0, 0.0, End), -59777.15, 0.005);
+    }
+
+    #[test]
+    fn test_pmt_ipmt_ppmt() {
+        assert_close(pmt(0.08 / 12.0, 10.0, 10000.0, 0.0, End).unwrap(), -1037.03, 0.005);
+        assert_close(pmt(0.08 / 12.0, 10.0, 10000.0, 0.0, Beginning).unwrap(), -1030.16, 0.005);
+        assert_close(pmt(0.06 / 12.0, 18.0 * 12.0, 0.0, 50000.0, End).unwrap(), -129.08, 0.005);
+
+        assert_close(ipmt(0.1 / 12.0, 1.0, 36.0, 8000.0, 0.0, End).unwrap(), -66.67, 0.005);
+        assert_close(ipmt(0.1, 3.0, 3.0, 8000.0, 0.0, End).unwrap(), -292.45, 0.005);
+        assert_close(ppmt(0.1 / 12.0, 1.0, 24.0, 2000.0, 0.0, End).unwrap(), -75.62, 0.005);
+        assert_close(ppmt(0.08, 10.0, 10.0, 200000.0, 0.0, End).unwrap(), -27598.05, 0.005);
+
+        // Interest and principal add up to the payment in every period
+        let payment = pmt(0.05 / 12.0, 60.0, 20000.0, 0.0, Beginning).unwrap();
+        for per in [1.0, 2.0, 30.0, 60.0] {
+            let split = ipmt(0.05 / 12.0, per, 60.0, 20000.0, 0.0, Beginning).unwrap()
+                + ppmt(0.05 / 12.0, per, 60.0, 20000.0, 0.0, Beginning).unwrap();
+            assert_close(split, payment, 1e-9);
+        }
+        assert_eq!(ipmt(0.05 / 12.0, 1.0, 60.0, 20000.0, 0.0, Beginning), Ok(0.0));
+        // Paid at the start of period 2: a month of interest on 8,000 less the first payment of 256.00
+        assert_close(ipmt(0.1 / 12.0, 2.0, 36.0, 8000.0, 0.0, Beginning).unwrap(), -64.53, 0.005);
+        assert!(ipmt(0.1, 4.0, 3.0, 8000.0, 0.0, End).is_err());
+    }
+
+    #[test]
+    fn test_nper_and_rate() {
+        assert_close(nper(0.12 / 12.0, -100.0, -1000.0, 10000.0, Beginning).unwrap(), 59.6738657, 1e-7);
+        assert_close(nper(0.01, -100.0, -1000.0, 10000.0, End).unwrap(), 60.0821229, 1e-7);
+        assert_close(nper(0.01, -100.0, -1000.0, 0.0, End).unwrap(), -9.57859404, 1e-7);
+
+        assert_close(rate(48.0, -200.0, 8000.0, 0.0, End, 0.1).unwrap(), 0.0077014724, 1e-9);
+        assert_close(rate(48.0, -200.0, 8000.0, 0.0, End, 0.1).unwrap() * 12.0, 0.09241767, 1e-7);
+    }
+
+    #[test]
+    fn test_effect_and_nominal() {
+        assert_close(effect(0.0525, 4.0).unwrap(), 0.053542667, 1e-9);
+        assert_close(nominal(0.053543, 4.0).unwrap(), 0.05250032, 1e-8);
+        assert_close(nominal(effect(0.07, 12.0).unwrap(), 12.0).unwrap(), 0.07, 1e-12);
+        assert!(effect(0.05, 0.5).is_err());
+    }
+}
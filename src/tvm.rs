@@ -0,0 +1,269 @@
+use crate::error::CalcError;
+use crate::{
+    calculate_compound_interest_with_contributions, solver, CompoundInterestParams, Compounding, Contribution,
+    ContributionTiming, Escalation, PaymentFrequency, MAX_SOLVED_RATE, MAX_YEARS_TO_TARGET, MIN_SOLVED_RATE,
+};
+
+/// A time-value-of-money problem with exactly one unknown
+///
+/// Leave the quantity to solve for as `None`. The conventions are those of
+/// `calculate_compound_interest_with_contributions`: the present value is the
+/// starting balance, payments are deposits made at `frequency` (negative for
+/// withdrawals), and the future value is the balance after `years`.
+#[derive(Debug, Clone)]
+pub struct Tvm {
+    /// Starting balance
+    pub present_value: Option<f64>,
+    /// Balance at the end of the horizon
+    pub future_value: Option<f64>,
+    /// Amount paid in each period (negative for withdrawals)
+    pub payment: Option<f64>,
+    /// Length of the horizon in years; the number of payments is this times `frequency.periods_per_year()`
+    pub years: Option<f64>,
+    /// Annual interest rate (as a decimal)
+    pub annual_rate: Option<f64>,
+    /// How often interest is compounded
+    pub compounding: Compounding,
+    /// How often payments are made
+    pub frequency: PaymentFrequency,
+    /// Whether each payment is made at the beginning or end of its period
+    pub timing: ContributionTiming,
+}
+
+/// The quantity a `Tvm` problem was solved for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TvmUnknown {
+    /// The starting balance
+    PresentValue,
+    /// The balance at the end of the horizon
+    FutureValue,
+    /// The amount paid in each period
+    Payment,
+    /// The length of the horizon in years
+    Years,
+    /// The annual interest rate
+    AnnualRate,
+}
+
+/// A fully solved time-value-of-money problem
+#[derive(Debug, Clone, PartialEq)]
+pub struct TvmSolution {
+    /// Starting balance
+    pub present_value: f64,
+    /// Balance at the end of the horizon
+    pub future_value: f64,
+    /// Amount paid in each period
+    pub payment: f64,
+    /// Length of the horizon in years
+    pub years: f64,
+    /// Annual interest rate (as a decimal)
+    pub annual_rate: f64,
+    /// Which of the values was solved for
+    pub solved_for: TvmUnknown,
+}
+
+impl Tvm {
+    /// Future value of the given inputs under this problem's conventions
+    fn future_value_of(&self, present_value: f64, payment: f64, years: f64, annual_rate: f64) -> f64 {
+        let params = CompoundInterestParams::new(present_value, annual_rate, self.compounding, years);
+        let contribution = Contribution {
+            amount: payment,
+            frequency: self.frequency,
+            timing: self.timing,
+            escalation: Escalation::None,
+        };
+        calculate_compound_interest_with_contributions(&params, &contribution).final_amount
+    }
+}
+
+/// Solve a time-value-of-money problem for its one unknown
+///
+/// Present value, future value and payment are solved directly, since the
+/// future value is linear in each of them. Years and rate are found
+/// numerically. Over time the balance moves steadily one way, towards or away
+/// from the target, so the years solver measures progress in the direction of
+/// the target. The future value may rise or fall with the rate: it falls for a
+/// loan (a negative present value repaid by deposits), so the rate solver
+/// flips its objective when the lowest rate it tries already overshoots.
+pub fn solve_tvm(problem: &Tvm) -> Result<TvmSolution, CalcError> {
+    let unknowns = [
+        (problem.present_value, TvmUnknown::PresentValue),
+        (problem.future_value, TvmUnknown::FutureValue),
+        (problem.payment, TvmUnknown::Payment),
+        (problem.years, TvmUnknown::Years),
+        (problem.annual_rate, TvmUnknown::AnnualRate),
+    ];
+    let mut missing = unknowns.iter().filter(|(value, _)| value.is_none()).map(|(_, unknown)| *unknown);
+    let solved_for = match (missing.next(), missing.next()) {
+        (Some(unknown), None) => unknown,
+        _ => return Err(CalcError::InvalidInput("exactly one value must be left unknown".to_string())),
+    };
+    if problem.years.is_some_and(|years| years < 0.0) {
+        return Err(CalcError::InvalidInput("the time period must not be negative".to_string()));
+    }
+
+    let present_value = problem.present_value.unwrap_or_default();
+    let future_value = problem.future_value.unwrap_or_default();
+    let payment = problem.payment.unwrap_or_default();
+    let years = problem.years.unwrap_or_default();
+    let annual_rate = problem.annual_rate.unwrap_or_default();
+
+    let mut solution = TvmSolution {
+        present_value,
+        future_value,
+        payment,
+        years,
+        annual_rate,
+        solved_for,
+    };
+    match solved_for {
+        TvmUnknown::FutureValue => {
+            solution.future_value = problem.future_value_of(present_value, payment, years, annual_rate);
+        }
+        TvmUnknown::PresentValue => {
+            let growth = problem.compounding.growth_factor(annual_rate, years);
+            let payments_only = problem.future_value_of(0.0, payment, years, annual_rate);
+            solution.present_value = (future_value - payments_only) / growth;
+        }
+        TvmUnknown::Payment => {
+            let unit_value = problem.future_value_of(0.0, 1.0, years, annual_rate);
+            if unit_value == 0.0 {
+                return Err(CalcError::Unreachable);
+            }
+            let principal_only = problem.future_value_of(present_value, 0.0, years, annual_rate);
+            solution.payment = (future_value - principal_only) / unit_value;
+        }
+        TvmUnknown::Years => {
+            // Measure progress towards the target in whichever direction it lies
+            let direction = if future_value >= present_value { 1.0 } else { -1.0 };
+            solution.years = solver::solve_increasing(
+                |years| direction * (problem.future_value_of(present_value, payment, years, annual_rate) - future_value),
+                1.0,
+                MAX_YEARS_TO_TARGET,
+                1e-9,
+            )
+            .ok_or(CalcError::Unreachable)?;
+        }
+        TvmUnknown::AnnualRate => {
+            if years == 0.0 {
+                return Err(CalcError::InvalidInput("the time period must be positive".to_string()));
+            }
+            let shortfall = |rate: f64| problem.future_value_of(present_value, payment, years, rate) - future_value;
+            // Search in whichever direction the future value moves with the rate
+            let direction = if shortfall(MIN_SOLVED_RATE) > 0.0 { -1.0 } else { 1.0 };
+            solution.annual_rate = solver::find_root_increasing(
+                |rate| direction * shortfall(rate),
+                MIN_SOLVED_RATE,
+                1.0,
+                MAX_SOLVED_RATE,
+                1e-12,
+            )?;
+        }
+    }
+
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem() -> Tvm {
+        Tvm {
+            present_value: Some(5000.0),
+            future_value: None,
+            payment: Some(250.0),
+            years: Some(12.0),
+            annual_rate: Some(0.055),
+            compounding: Compounding::Quarterly,
+            frequency: PaymentFrequency::Monthly,
+            timing: ContributionTiming::Beginning,
+        }
+    }
+
+    #[test]
+    fn test_solves_each_unknown() {
+        let known = solve_tvm(&problem()).unwrap();
+        assert_eq!(known.solved_for, TvmUnknown::FutureValue);
+
+        let complete = Tvm {
+            future_value: Some(known.future_value),
+            ..problem()
+        };
+        let cases = [
+            (Tvm { present_value: None, ..complete.clone() }, known.present_value),
+            (Tvm { payment: None, ..complete.clone() }, known.payment),
+            (Tvm { years: None, ..complete.clone() }, known.years),
+            (Tvm { annual_rate: None, ..complete.clone() }, known.annual_rate),
+        ];
+        for (case, expected) in cases {
+            let solution = solve_tvm(&case).unwrap();
+            let value = match solution.solved_for {
+                TvmUnknown::PresentValue => solution.present_value,
+                TvmUnknown::Payment => solution.payment,
+                TvmUnknown::Years => solution.years,
+                TvmUnknown::AnnualRate => solution.annual_rate,
+                TvmUnknown::FutureValue => unreachable!(),
+            };
+            assert!((value - expected).abs() < 1e-6, "{:?}: {} != {}", solution.solved_for, value, expected);
+        }
+    }
+
+    #[test]
+    fn test_withdrawals_run_balance_down() {
+        // Drawing 1,000 a month from 100,000 at 0% empties it in 100 months
+        let solution = solve_tvm(&Tvm {
+            present_value: Some(100000.0),
+            future_value: Some(0.0),
+            payment: Some(-1000.0),
+            years: None,
+            annual_rate: Some(0.0),
+            compounding: Compounding::Monthly,
+            frequency: PaymentFrequency::Monthly,
+            timing: ContributionTiming::End,
+        })
+        .unwrap();
+        assert!((solution.years - 100.0 / 12.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_loan_rate_and_term() {
+        // Borrowing 8,000 and repaying 200 a month for four years
+        let loan = Tvm {
+            present_value: Some(-8000.0),
+            future_value: Some(0.0),
+            payment: Some(200.0),
+            years: Some(4.0),
+            annual_rate: None,
+            compounding: Compounding::Monthly,
+            frequency: PaymentFrequency::Monthly,
+            timing: ContributionTiming::End,
+        };
+        let solution = solve_tvm(&loan).unwrap();
+        let mirrored = solve_tvm(&Tvm {
+            present_value: Some(8000.0),
+            payment: Some(-200.0),
+            ..loan.clone()
+        })
+        .unwrap();
+        assert!((solution.annual_rate - 0.0924).abs() < 1e-4);
+        assert!((solution.annual_rate - mirrored.annual_rate).abs() < 1e-9);
+
+        let term = solve_tvm(&Tvm {
+            years: None,
+            annual_rate: Some(solution.annual_rate),
+            ..loan
+        })
+        .unwrap();
+        assert!((term.years - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_requires_exactly_one_unknown() {
+        let two_unknowns = Tvm {
+            payment: None,
+            ..problem()
+        };
+        assert!(matches!(solve_tvm(&two_unknowns), Err(CalcError::InvalidInput(_))));
+    }
+}
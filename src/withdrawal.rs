@@ -0,0 +1,462 @@
+use std::cmp::Ordering;
+
+use crate::error::CalcError;
+use crate::random::Rng;
+use crate::simulation::PercentileReport;
+use crate::{Compounding, ContributionTiming, Escalation, PaymentFrequency};
+
+/// Represents a plan for drawing down a balance with regular withdrawals
+#[derive(Debug, Clone)]
+pub struct WithdrawalPlan {
+    /// Balance at the start of the plan
+    pub balance: f64,
+    /// Annual interest rate earned on the remaining balance (as a decimal)
+    pub annual_rate: f64,
+    /// How often interest is compounded
+    pub compounding: Compounding,
+    /// Amount withdrawn each period during the first year
+    pub withdrawal: f64,
+    /// How often withdrawals are made
+    pub frequency: PaymentFrequency,
+    /// Whether each withdrawal is taken at the beginning or end of its period
+    pub timing: ContributionTiming,
+    /// How the withdrawal grows each year, e.g. `Escalation::Percent(inflation)`
+    pub indexation: Escalation,
+    /// Maximum number of years to project
+    pub years: f64,
+    /// Expected annual inflation rate used for the real results (0 for none)
+    pub inflation_rate: f64,
+}
+
+/// One withdrawal period of a decumulation schedule
+#[derive(Debug, Clone)]
+pub struct WithdrawalRow {
+    /// Period number, starting at 1
+    pub period: u32,
+    /// Year the period falls in, starting at 1
+    pub year: u32,
+    /// Amount withdrawn this period
+    pub withdrawal: f64,
+    /// Interest earned this period
+    pub interest: f64,
+    /// Balance left at the end of the period
+    pub balance: f64,
+    /// Amount withdrawn this period in today's money
+    pub real_withdrawal: f64,
+    /// Balance left at the end of the period in today's money
+    pub real_balance: f64,
+}
+
+/// One year of a decumulation schedule
+#[derive(Debug, Clone)]
+pub struct WithdrawalYear {
+    /// Year number, starting at 1
+    pub year: u32,
+    /// Amount withdrawn during the year
+    pub withdrawn: f64,
+    /// Interest earned during the year
+    pub interest: f64,
+    /// Balance left at the end of the year
+    pub balance: f64,
+    /// Amount withdrawn during the year in today's money
+    pub real_withdrawn: f64,
+    /// Balance left at the end of the year in today's money
+    pub real_balance: f64,
+}
+
+/// Represents the result of a withdrawal plan projection
+#[derive(Debug, Clone)]
+pub struct WithdrawalResult {
+    /// Every withdrawal period until the money runs out or the horizon ends
+    pub rows: Vec<WithdrawalRow>,
+    /// Period in which the balance ran out, if it did within the horizon
+    pub depletion_period: Option<u32>,
+    /// Years the money lasted, if it ran out within the horizon
+    pub years_lasted: Option<f64>,
+    /// Total amount withdrawn
+    pub total_withdrawn: f64,
+    /// Total interest earned
+    pub total_interest: f64,
+    /// Balance left at the end of the projection
+    pub final_balance: f64,
+    /// Total amount withdrawn, each withdrawal in today's money
+    pub real_total_withdrawn: f64,
+    /// Balance left at the end of the projection in today's money
+    pub real_final_balance: f64,
+}
+
+impl WithdrawalResult {
+    /// Summarize the schedule one row per year
+    pub fn yearly(&self) -> Vec<WithdrawalYear> {
+        let mut years: Vec<WithdrawalYear> = Vec::new();
+        for row in &self.rows {
+            match years.last_mut() {
+                Some(last) if last.year == row.year => {
+                    last.withdrawn += row.withdrawal;
+                    last.interest += row.interest;
+                    last.balance = row.balance;
+                    last.real_withdrawn += row.real_withdrawal;
+                    last.real_balance = row.real_balance;
+                }
+                _ => years.push(WithdrawalYear {
+                    year: row.year,
+                    withdrawn: row.withdrawal,
+                    interest: row.interest,
+                    balance: row.balance,
+                    real_withdrawn: row.real_withdrawal,
+                    real_balance: row.real_balance,
+                }),
+            }
+        }
+        years
+    }
+}
+
+/// Project a withdrawal plan period by period
+///
+/// Interest is earned at the rate per withdrawal period equivalent to the
+/// compounding frequency, the same convention used for contributions. When the
+/// balance cannot cover a withdrawal, whatever is left is withdrawn and the
+/// plan is marked as depleted. The real values deflate each withdrawal from the
+/// date it is taken and each balance from the end of its period.
+pub fn calculate_withdrawals(plan: &WithdrawalPlan) -> WithdrawalResult {
+    let periods_per_year = plan.frequency.periods_per_year() as f64;
+    let periodic_rate = plan.compounding.equivalent_periodic_rate(plan.annual_rate, periods_per_year);
+    project_withdrawals(plan, |_| periodic_rate)
+}
+
+/// Project a withdrawal plan using the given return for each period, counting from 0
+fn project_withdrawals<F: FnMut(u32) -> f64>(plan: &WithdrawalPlan, mut periodic_return: F) -> WithdrawalResult {
+    let periods_per_year = plan.frequency.periods_per_year();
+    let total_periods = (plan.years * periods_per_year as f64).round() as u32;
+    let deflate = |amount: f64, period: u32| {
+        amount / Compounding::Annually.growth_factor(plan.inflation_rate, period as f64 / periods_per_year as f64)
+    };
+
+    let mut rows = Vec::new();
+    let mut balance = plan.balance;
+    let mut total_withdrawn = 0.0;
+    let mut total_interest = 0.0;
+    let mut real_total_withdrawn = 0.0;
+    let mut depletion_period = None;
+
+    for period in 0..total_periods {
+        let year = period / periods_per_year;
+        let rate = periodic_return(period);
+        let wanted = plan.indexation.amount_in_year(plan.withdrawal, year);
+
+        let (withdrawal, interest) = match plan.timing {
+            ContributionTiming::Beginning => {
+                let withdrawal = wanted.min(balance);
+                let interest = (balance - withdrawal) * rate;
+                (withdrawal, interest)
+            }
+            ContributionTiming::End => {
+                let interest = balance * rate;
+                (wanted.min(balance + interest), interest)
+            }
+        };
+        balance = (balance + interest - withdrawal).max(0.0);
+        total_withdrawn += withdrawal;
+        total_interest += interest;
+        let withdrawn_at = match plan.timing {
+            ContributionTiming::Beginning => period,
+            ContributionTiming::End => period + 1,
+        };
+        let real_withdrawal = deflate(withdrawal, withdrawn_at);
+        real_total_withdrawn += real_withdrawal;
+
+        rows.push(WithdrawalRow {
+            period: period + 1,
+            year: year + 1,
+            withdrawal,
+            interest,
+            balance,
+            real_withdrawal,
+            real_balance: deflate(balance, period + 1),
+        });
+
+        if withdrawal < wanted || balance <= 0.0 {
+            depletion_period = Some(period + 1);
+            break;
+        }
+    }
+
+    let real_final_balance = rows.last().map_or(balance, |row| row.real_balance);
+    WithdrawalResult {
+        rows,
+        depletion_period,
+        years_lasted: depletion_period.map(|period| period as f64 / periods_per_year as f64),
+        total_withdrawn,
+        total_interest,
+        final_balance: balance,
+        real_total_withdrawn,
+        real_final_balance,
+    }
+}
+
+/// A named sequence of periodic returns
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnSequence {
+    /// Name shown in reports, such as "reversed"
+    pub label: String,
+    /// Return for each withdrawal period in order (as decimals, e.g., 0.01 for 1%)
+    pub returns: Vec<f64>,
+}
+
+/// How a withdrawal plan fared under one sequence of returns
+#[derive(Debug, Clone)]
+pub struct SequenceOutcome {
+    /// Label of the sequence
+    pub label: String,
+    /// Full projection under the sequence
+    pub result: WithdrawalResult,
+}
+
+/// Balances at the end of one year under each sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceYear {
+    /// Year number, starting at 1
+    pub year: u32,
+    /// Balance at the end of the year under each sequence, in the order of the outcomes (0 once depleted)
+    pub balances: Vec<f64>,
+}
+
+/// Comparison of a withdrawal plan across several return sequences
+#[derive(Debug, Clone)]
+pub struct SequenceRisk {
+    /// Outcome under each sequence, in the order given
+    pub outcomes: Vec<SequenceOutcome>,
+    /// Index of the outcome that lasted longest, or ended with most if several lasted
+    pub best: usize,
+    /// Index of the outcome that ran out first, or ended with least if none ran out
+    pub worst: usize,
+    /// Share of sequences under which the money ran out
+    pub depletion_rate: f64,
+    /// Distribution of the final balance across the sequences
+    pub ending_balance: PercentileReport,
+}
+
+impl SequenceRisk {
+    /// Balance at the end of each year under every sequence, to set beside a
+    /// year-by-year breakdown
+    pub fn yearly_balances(&self) -> Vec<SequenceYear> {
+        let yearly: Vec<Vec<WithdrawalYear>> = self.outcomes.iter().map(|outcome| outcome.result.yearly()).collect();
+        let years = yearly.iter().map(|rows| rows.len()).max().unwrap_or(0);
+        (0..years)
+            .map(|index| SequenceYear {
+                year: index as u32 + 1,
+                balances: yearly.iter().map(|rows| rows.get(index).map_or(0.0, |row| row.balance)).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Project a withdrawal plan with a given return for each period
+///
+/// `returns` replaces `plan.annual_rate` and `plan.compounding`, one return per
+/// withdrawal period, and must cover the plan's horizon.
+pub fn calculate_withdrawals_with_returns(plan: &WithdrawalPlan, returns: &[f64]) -> Result<WithdrawalResult, CalcError> {
+    let periods = (plan.years * plan.frequency.periods_per_year() as f64).round() as usize;
+    if returns.len() < periods {
+        return Err(CalcError::InvalidInput(format!(
+            "{} returns were given for a plan of {} periods",
+            returns.len(),
+            periods
+        )));
+    }
+    Ok(project_withdrawals(plan, |period| returns[period as usize]))
+}
+
+/// Reorderings of one return sequence that share its average return
+///
+/// Gives the sequence as given, reversed, worst returns first, best returns
+/// first, and `shuffles` random orders drawn from `seed`.
+pub fn reorder_returns(returns: &[f64], shuffles: u32, seed: u64) -> Vec<ReturnSequence> {
+    let sequence = |label: &str, returns: Vec<f64>| ReturnSequence {
+        label: label.to_string(),
+        returns,
+    };
+    let mut worst_first = returns.to_vec();
+    worst_first.sort_by(f64::total_cmp);
+    let best_first: Vec<f64> = worst_first.iter().rev().copied().collect();
+
+    let mut sequences = vec![
+        sequence("as given", returns.to_vec()),
+        sequence("reversed", returns.iter().rev().copied().collect()),
+        sequence("worst first", worst_first),
+        sequence("best first", best_first),
+    ];
+
+    let mut rng = Rng::new(seed);
+    for shuffle in 1..=shuffles {
+        // Fisher-Yates shuffle
+        let mut shuffled = returns.to_vec();
+        for i in (1..shuffled.len()).rev() {
+            shuffled.swap(i, rng.below(i + 1));
+        }
+        sequences.push(sequence(&format!("shuffle {}", shuffle), shuffled));
+    }
+    sequences
+}
+
+/// Order outcomes from worst to best: running out sooner is worse, and among
+/// plans that last, a lower final balance is worse
+fn compare_outcomes(a: &WithdrawalResult, b: &WithdrawalResult) -> Ordering {
+    match (a.depletion_period, b.depletion_period) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.final_balance.total_cmp(&b.final_balance),
+    }
+}
+
+/// Compare a withdrawal plan across several return sequences
+///
+/// Each sequence is run with `calculate_withdrawals_with_returns`. Passing
+/// the output of `reorder_returns` shows how much the order of the same
+/// returns changes when the money runs out and what is left.
+pub fn analyze_sequence_risk(plan: &WithdrawalPlan, sequences: &[ReturnSequence]) -> Result<SequenceRisk, CalcError> {
+    let outcomes = sequences
+        .iter()
+        .map(|sequence| {
+            let result = calculate_withdrawals_with_returns(plan, &sequence.returns)
+                .map_err(|error| CalcError::InvalidInput(format!("sequence '{}': {}", sequence.label, error)))?;
+            Ok(SequenceOutcome {
+                label: sequence.label.clone(),
+                result,
+            })
+        })
+        .collect::<Result<Vec<_>, CalcError>>()?;
+
+    let balances: Vec<f64> = outcomes.iter().map(|outcome| outcome.result.final_balance).collect();
+    let ending_balance = PercentileReport::from_outcomes(&balances)
+        .ok_or_else(|| CalcError::InvalidInput("at least one return sequence is needed".to_string()))?;
+    // Ties go to the sequence listed first
+    let compare = |&a: &usize, &b: &usize| compare_outcomes(&outcomes[a].result, &outcomes[b].result);
+    let worst = (0..outcomes.len()).min_by(compare).unwrap_or(0);
+    let best = (0..outcomes.len()).rev().max_by(compare).unwrap_or(0);
+    let depleted = outcomes.iter().filter(|outcome| outcome.result.depletion_period.is_some()).count();
+
+    Ok(SequenceRisk {
+        best,
+        worst,
+        depletion_rate: depleted as f64 / outcomes.len() as f64,
+        ending_balance,
+        outcomes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(balance: f64, annual_rate: f64, withdrawal: f64) -> WithdrawalPlan {
+        WithdrawalPlan {
+            balance,
+            annual_rate,
+            compounding: Compounding::Monthly,
+            withdrawal,
+            frequency: PaymentFrequency::Monthly,
+            timing: ContributionTiming::End,
+            indexation: Escalation::None,
+            years: 50.0,
+            inflation_rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_depletion_without_interest() {
+        let result = calculate_withdrawals(&plan(12000.0, 0.0, 1000.0));
+
+        assert_eq!(result.depletion_period, Some(12));
+        assert_eq!(result.years_lasted, Some(1.0));
+        assert!((result.total_withdrawn - 12000.0).abs() < 1e-9);
+        assert_eq!(result.yearly().len(), 1);
+    }
+
+    #[test]
+    fn test_interest_covers_withdrawals() {
+        // 0.5% a month on 100,000 pays exactly the 500 withdrawn
+        let result = calculate_withdrawals(&plan(100000.0, 0.06, 500.0));
+
+        assert_eq!(result.depletion_period, None);
+        assert!((result.final_balance - 100000.0).abs() < 1e-6);
+        assert_eq!(result.yearly().len(), 50);
+    }
+
+    #[test]
+    fn test_order_of_returns_matters() {
+        // Ten years of withdrawals with one crash year, early or late
+        let plan = WithdrawalPlan {
+            frequency: PaymentFrequency::Annually,
+            years: 10.0,
+            ..plan(100000.0, 0.0, 12000.0)
+        };
+        let mut returns = vec![0.07; 10];
+        returns[0] = -0.4;
+
+        let constant = calculate_withdrawals_with_returns(&plan, &[0.07; 10]).unwrap();
+        let expected = calculate_withdrawals(&WithdrawalPlan {
+            annual_rate: 0.07,
+            compounding: Compounding::Annually,
+            ..plan.clone()
+        });
+        assert!((constant.final_balance - expected.final_balance).abs() < 1e-6);
+
+        let risk = analyze_sequence_risk(&plan, &reorder_returns(&returns, 20, 5)).unwrap();
+        assert_eq!(risk.outcomes.len(), 24);
+        // The crash up front is the worst order, at the end the best
+        assert_eq!(risk.outcomes[risk.worst].label, "as given");
+        assert!(risk.outcomes[0].result.depletion_period.is_some());
+        assert_eq!(risk.outcomes[1].result.depletion_period, None);
+        assert_eq!(risk.outcomes[risk.best].label, "reversed");
+        assert!(risk.depletion_rate > 0.0 && risk.depletion_rate < 1.0);
+
+        let years = risk.yearly_balances();
+        assert_eq!(years.len(), 10);
+        assert_eq!(years[9].balances.len(), 24);
+        assert_eq!(years[9].balances[0], 0.0);
+        assert!((years[9].balances[1] - risk.outcomes[1].result.final_balance).abs() < 1e-9);
+
+        assert!(calculate_withdrawals_with_returns(&plan, &[0.07; 9]).is_err());
+        assert!(analyze_sequence_risk(&plan, &[]).is_err());
+    }
+
+    #[test]
+    fn test_inflation_indexing_runs_out_sooner() {
+        let fixed = calculate_withdrawals(&plan(300000.0, 0.05, 2000.0));
+        let indexed = calculate_withdrawals(&WithdrawalPlan {
+            indexation: Escalation::Percent(0.03),
+            ..plan(300000.0, 0.05, 2000.0)
+        });
+
+        assert!(indexed.depletion_period.unwrap() < fixed.depletion_period.unwrap());
+    }
+
+    #[test]
+    fn test_real_values_in_todays_money() {
+        // Withdrawals indexed to 3% inflation stay level in today's money
+        let result = calculate_withdrawals(&WithdrawalPlan {
+            compounding: Compounding::Annually,
+            frequency: PaymentFrequency::Annually,
+            timing: ContributionTiming::Beginning,
+            indexation: Escalation::Percent(0.03),
+            years: 5.0,
+            inflation_rate: 0.03,
+            ..plan(100000.0, 0.03, 10000.0)
+        });
+        assert!(result.rows.iter().all(|row| (row.real_withdrawal - 10000.0).abs() < 1e-6));
+        assert!((result.real_total_withdrawn - 50000.0).abs() < 1e-6);
+
+        // Earning exactly the inflation rate, the real balance only falls by what was withdrawn
+        assert!((result.real_final_balance - 50000.0).abs() < 1e-6);
+        let years = result.yearly();
+        assert!((years[0].real_balance - 90000.0).abs() < 1e-6);
+        assert!((years[0].real_withdrawn - 10000.0).abs() < 1e-6);
+
+        // Without inflation the real values are the nominal ones
+        let nominal = calculate_withdrawals(&plan(100000.0, 0.05, 500.0));
+        assert_eq!(nominal.real_final_balance, nominal.final_balance);
+    }
+}